@@ -0,0 +1,279 @@
+use anyhow::{anyhow, Result};
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+use crate::Theme;
+
+/// Parsed command line
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Cli {
+	pub subcommand: Subcommand,
+	/// Overrides `RUST_LOG`
+	pub log_level: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Subcommand {
+	Present(PresentOptions),
+	/// Loads the presentation and reports what's wrong with it
+	Check(PathBuf),
+	/// Writes the text of the slides, to stdout without an output
+	Export {
+		path: PathBuf,
+		output: Option<PathBuf>,
+	},
+	Help,
+}
+
+impl Default for Subcommand {
+	fn default() -> Self {
+		Subcommand::Present(Default::default())
+	}
+}
+
+/// How the presentation is shown, passed to the app as its flags
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PresentOptions {
+	/// Welcome screen is shown without one
+	pub path: Option<PathBuf>,
+	/// Index of the slide to start at, given 1-based like in the footer
+	pub start_slide: Option<usize>,
+	/// Starts at the slide the presentation was left at, instead of offering it
+	pub resume: bool,
+	pub fullscreen: bool,
+	/// Overrides the front matter
+	pub theme: Option<Theme>,
+	/// Width to height ratio slides are letterboxed to
+	pub aspect: Option<f32>,
+	pub no_watch: bool,
+}
+
+const LOG_LEVELS: [&str; 6] = ["off", "error", "warn", "info", "debug", "trace"];
+
+pub fn usage() -> String {
+	format!(
+		r#"Usage: {name} [present] [OPTIONS] [FILE]
+       {name} check FILE
+       {name} export [-o OUTPUT] FILE
+
+Shows the markdown FILE as a presentation. Without one, recent presentations are listed.
+
+Commands:
+  present              Show the presentation (default)
+  check                Load the presentation and report problems, without opening a window
+  export               Write the text of the slides as an outline
+  help                 Show this help
+
+Options of present:
+  --start-slide N      Start at slide N
+  --resume             Start at the slide the presentation was left at
+  --fullscreen         Start in fullscreen
+  --theme THEME        dark or light, overrides the front matter
+  --aspect RATIO       Letterbox slides to a width to height ratio, e.g. 16:9
+  --no-watch           Don't reload the presentation when its files change
+
+Options of export:
+  -o, --output OUTPUT  File to write to instead of stdout
+
+Options:
+  --log-level LEVEL    One of {levels} (default: debug)
+  -h, --help           Show this help
+"#,
+		name = env!("CARGO_PKG_NAME"),
+		levels = LOG_LEVELS.join(", "),
+	)
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Kind {
+	Present,
+	Check,
+	Export,
+}
+
+impl Cli {
+	/// Parses the arguments, without the program name. Options can be given as `--option value`
+	/// or `--option=value`.
+	pub fn parse(args: impl IntoIterator<Item = OsString>) -> Result<Self> {
+		let mut args = args.into_iter().peekable();
+		let mut cli = Cli::default();
+
+		let kind = match args.peek().and_then(|arg| arg.to_str()) {
+			Some("present") => Kind::Present,
+			Some("check") => Kind::Check,
+			Some("export") => Kind::Export,
+			Some("help") => {
+				cli.subcommand = Subcommand::Help;
+				return Ok(cli);
+			}
+			_ => Kind::Present,
+		};
+		if kind != Kind::Present || args.peek().map_or(false, |arg| arg == "present") {
+			args.next();
+		}
+
+		let mut path = None;
+		let mut options = PresentOptions::default();
+		let mut output = None;
+		while let Some(arg) = args.next() {
+			let flag = match arg.to_str() {
+				Some(flag) if flag.starts_with('-') && flag != "-" => flag.to_string(),
+				_ => {
+					if path.is_some() {
+						anyhow::bail!("Unexpected argument {}", arg.to_string_lossy());
+					}
+					path = Some(PathBuf::from(arg));
+					continue;
+				}
+			};
+			let (flag, inline_value) = match flag.split_once('=') {
+				Some((flag, value)) => (flag, Some(value)),
+				None => (flag.as_str(), None),
+			};
+			let mut value = || -> Result<OsString> {
+				match inline_value {
+					Some(v) => Ok(OsString::from(v)),
+					None => args
+						.next()
+						.ok_or_else(|| anyhow!("Missing value of {}", flag)),
+				}
+			};
+
+			match (kind, flag) {
+				(_, "-h" | "--help") => {
+					cli.subcommand = Subcommand::Help;
+					return Ok(cli);
+				}
+				(_, "--log-level") => {
+					let level = utf8(value()?)?.to_ascii_lowercase();
+					if !LOG_LEVELS.contains(&level.as_str()) {
+						anyhow::bail!("Unknown log level {}", level);
+					}
+					cli.log_level = Some(level);
+				}
+				(Kind::Present, "--start-slide") => {
+					let number = utf8(value()?)?;
+					match number.parse::<usize>() {
+						Ok(n) if n >= 1 => options.start_slide = Some(n - 1),
+						_ => anyhow::bail!("Invalid slide number {}", number),
+					}
+				}
+				(Kind::Present, "--resume") => options.resume = true,
+				(Kind::Present, "--fullscreen") => options.fullscreen = true,
+				(Kind::Present, "--theme") => options.theme = Some(utf8(value()?)?.parse()?),
+				(Kind::Present, "--aspect") => {
+					options.aspect = Some(parse_aspect(&utf8(value()?)?)?)
+				}
+				(Kind::Present, "--no-watch") => options.no_watch = true,
+				(Kind::Export, "-o" | "--output") => output = Some(PathBuf::from(value()?)),
+				_ => anyhow::bail!("Unknown option {}", flag),
+			}
+		}
+
+		cli.subcommand = match kind {
+			Kind::Present => Subcommand::Present(PresentOptions { path, ..options }),
+			Kind::Check => {
+				Subcommand::Check(path.ok_or_else(|| anyhow!("check needs a presentation file"))?)
+			}
+			Kind::Export => Subcommand::Export {
+				path: path.ok_or_else(|| anyhow!("export needs a presentation file"))?,
+				output,
+			},
+		};
+		Ok(cli)
+	}
+}
+
+fn utf8(value: OsString) -> Result<String> {
+	value
+		.into_string()
+		.map_err(|value| anyhow!("Invalid value {}", value.to_string_lossy()))
+}
+
+/// `16:9`, `16/9` or `1.78`
+fn parse_aspect(s: &str) -> Result<f32> {
+	let aspect = match s.split_once([':', '/']) {
+		Some((width, height)) => width.trim().parse::<f32>()? / height.trim().parse::<f32>()?,
+		None => s.trim().parse::<f32>()?,
+	};
+	if !aspect.is_finite() || aspect <= 0.0 {
+		anyhow::bail!("Invalid aspect ratio {}", s);
+	}
+	Ok(aspect)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn parse(args: &[&str]) -> Result<Cli> {
+		Cli::parse(args.iter().map(OsString::from))
+	}
+
+	#[test]
+	fn parse_present() -> Result<()> {
+		assert_eq!(parse(&[])?, Cli::default());
+		assert_eq!(
+			parse(&[
+				"talk.md",
+				"--start-slide",
+				"3",
+				"--fullscreen",
+				"--theme=light",
+				"--aspect",
+				"16:10",
+				"--log-level",
+				"info",
+			])?,
+			Cli {
+				subcommand: Subcommand::Present(PresentOptions {
+					path: Some(PathBuf::from("talk.md")),
+					start_slide: Some(2),
+					fullscreen: true,
+					theme: Some(Theme::Light),
+					aspect: Some(1.6),
+					..Default::default()
+				}),
+				log_level: Some("info".to_string()),
+			}
+		);
+		assert_eq!(
+			parse(&["present", "--no-watch", "talk.md"])?.subcommand,
+			Subcommand::Present(PresentOptions {
+				path: Some(PathBuf::from("talk.md")),
+				no_watch: true,
+				..Default::default()
+			})
+		);
+
+		assert!(parse(&["--start-slide", "0"]).is_err());
+		assert!(parse(&["--start-slide"]).is_err());
+		assert!(parse(&["--theme", "solarized"]).is_err());
+		assert!(parse(&["--aspect", "16:0"]).is_err());
+		assert!(parse(&["a.md", "b.md"]).is_err());
+		assert!(parse(&["--unknown"]).is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn parse_subcommands() -> Result<()> {
+		assert_eq!(
+			parse(&["check", "talk.md"])?.subcommand,
+			Subcommand::Check(PathBuf::from("talk.md"))
+		);
+		assert_eq!(
+			parse(&["export", "-o", "talk.txt", "talk.md"])?.subcommand,
+			Subcommand::Export {
+				path: PathBuf::from("talk.md"),
+				output: Some(PathBuf::from("talk.txt")),
+			}
+		);
+		assert!(parse(&["check"]).is_err());
+		// Options of present don't apply to the other commands
+		assert!(parse(&["check", "--fullscreen", "talk.md"]).is_err());
+
+		assert_eq!(parse(&["help"])?.subcommand, Subcommand::Help);
+		assert_eq!(parse(&["export", "--help"])?.subcommand, Subcommand::Help);
+		Ok(())
+	}
+}
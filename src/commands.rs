@@ -1,24 +1,323 @@
 use anyhow::{anyhow, Context, Result};
 use log::error;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
 
 use crate::{
-	CodeBlockParams, FileWatch, HeaderSize, Image, Language, Presentation, Slide, SlideNode,
+	font_name, views, Animation, CodeBlockParams, Crop, FileWatch, GlyphCoverage, HeaderSize,
+	Image, ImageLocation, ImageParams, ImageSize, Language, Presentation, Slide, SlideNode,
+	VectorImage,
 };
 
-pub type LoadFromArgsResult = Result<Presentation>;
+pub type LoadResult = Result<Presentation>;
 
-pub async fn load_from_args() -> LoadFromArgsResult {
-	let file_path = std::env::args().skip(1).next();
-	match file_path {
-		Some(path) => load_from_file(&path).await,
+/// Loads the presentation at `path`, or the example without one. Images are downscaled to what
+/// can be seen of them on the `canvas`.
+pub async fn load(
+	path: Option<PathBuf>,
+	image_cache: ImageCache,
+	canvas: iced::Size,
+) -> LoadResult {
+	match path {
+		Some(path) => load_from_file(&path, image_cache, canvas).await,
 		None => load_example().await,
 	}
 }
 
-async fn load_from_file(path: &str) -> LoadFromArgsResult {
-	let path = Path::new(path);
+/// Asks for a presentation with the system file dialog, `None` if it was cancelled
+pub async fn pick_file() -> Option<PathBuf> {
+	rfd::AsyncFileDialog::new()
+		.set_title("Open presentation")
+		.add_filter("Markdown", &["md", "markdown"])
+		.pick_file()
+		.await
+		.map(|file| file.path().to_owned())
+}
+
+pub fn is_markdown(path: &Path) -> bool {
+	path.extension()
+		.map(|ext| ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown"))
+		.unwrap_or(false)
+}
+
+/// Decoded images of the presentation, reused across reloads for files that didn't change.
+/// Shared with the load command, so the app can keep it between reloads.
+#[derive(Debug, Clone, Default)]
+pub struct ImageCache(Arc<Mutex<HashMap<ImageKey, DecodedImage>>>);
+
+/// Params that change the decoded pixels are part of the key. Images are downscaled to what can
+/// be seen of them on the canvas, so the canvas and the params sizing them on it are too.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+struct ImageKey {
+	path: PathBuf,
+	modified: Option<SystemTime>,
+	len: u64,
+	crop: Option<Crop>,
+	corner_radius: Option<u32>,
+	canvas: (u32, u32),
+	scale: Option<u32>,
+	sizes: [Option<(bool, u32)>; 4],
+}
+
+impl ImageKey {
+	/// Key of the image at `path` as it's seen on the canvas
+	async fn new(path: &Path, params: &ImageParams, canvas: iced::Size) -> Result<Self> {
+		// Percent sizes and pixels with the same value differ
+		let size_key = |size: Option<ImageSize>| {
+			size.map(|size| match size {
+				ImageSize::Pixels(v) => (false, v.to_bits()),
+				ImageSize::Percent(v) => (true, v.to_bits()),
+			})
+		};
+		let metadata = async_fs::metadata(path).await?;
+		Ok(ImageKey {
+			path: async_fs::canonicalize(path).await?,
+			modified: metadata.modified().ok(),
+			len: metadata.len(),
+			crop: params.crop,
+			corner_radius: params.corner_radius.map(f32::to_bits),
+			canvas: (canvas.width.to_bits(), canvas.height.to_bits()),
+			scale: params.scale.map(f32::to_bits),
+			sizes: [
+				size_key(params.width),
+				size_key(params.height),
+				size_key(params.max_width),
+				size_key(params.max_height),
+			],
+		})
+	}
+}
+
+/// Image taken from the cache or being decoded on the blocking thread pool
+enum Decoding {
+	Cached(DecodedImage),
+	Started(tokio::task::JoinHandle<Result<DecodedImage>>),
+}
+
+impl Decoding {
+	fn start(
+		path: PathBuf,
+		params: &ImageParams,
+		canvas: iced::Size,
+		key: &ImageKey,
+		image_cache: &ImageCache,
+	) -> Self {
+		if let Some(decoded) = image_cache.0.lock().unwrap().get(key) {
+			return Decoding::Cached(decoded.clone());
+		}
+		let params = params.clone();
+		Decoding::Started(tokio::task::spawn_blocking(move || {
+			DecodedImage::decode(&path, &params, canvas)
+		}))
+	}
+
+	async fn finish(self) -> Result<DecodedImage> {
+		match self {
+			Decoding::Cached(decoded) => Ok(decoded),
+			Decoding::Started(task) => task.await?,
+		}
+	}
+}
+
+/// Decodings of the images of one load. Images shown the same way more than once share a key and
+/// are decoded once.
+#[derive(Default)]
+struct Decodings(HashMap<ImageKey, Decoding>);
+
+impl Decodings {
+	fn start(
+		&mut self,
+		path: PathBuf,
+		params: &ImageParams,
+		canvas: iced::Size,
+		key: &ImageKey,
+		image_cache: &ImageCache,
+	) {
+		if !self.0.contains_key(key) {
+			let decoding = Decoding::start(path, params, canvas, key, image_cache);
+			self.0.insert(key.clone(), decoding);
+		}
+	}
+
+	/// Waits for all of them. Errors are logged and kept as messages, so the images sharing a key
+	/// can all show them.
+	async fn finish(self) -> HashMap<ImageKey, Result<DecodedImage, String>> {
+		let mut decoded_images = HashMap::new();
+		for (key, decoding) in self.0 {
+			let decoded = decoding.finish().await.map_err(|e| {
+				log::error!("Failed to decode {}: {:?}", key.path.display(), e);
+				e.to_string()
+			});
+			decoded_images.insert(key, decoded);
+		}
+		decoded_images
+	}
+}
+
+#[derive(Debug, Clone)]
+pub enum DecodedImage {
+	Raster {
+		handle: iced::image::Handle,
+		animation: Option<Animation>,
+		source_size: iced::Size,
+	},
+	Vector(VectorImage),
+}
+
+impl DecodedImage {
+	fn decode(path: &Path, params: &ImageParams, canvas: iced::Size) -> Result<Self> {
+		if is_svg(path) {
+			if params.crop.is_some() || params.corner_radius.is_some() {
+				log::warn!("crop and corner_radius aren't supported for SVG images");
+			}
+			return Ok(DecodedImage::Vector(load_vector_image(path)?));
+		}
+		let (handle, animation, source_size) = decode_image(path, params, Some(canvas))?;
+		Ok(DecodedImage::Raster {
+			handle,
+			animation,
+			source_size,
+		})
+	}
+
+	pub fn apply_to(self, image: &mut Image) {
+		match self {
+			DecodedImage::Raster {
+				handle,
+				animation,
+				source_size,
+			} => {
+				image.handle = Some(handle);
+				image.animation = animation;
+				image.source_size = Some(source_size);
+			}
+			DecodedImage::Vector(vector) => image.vector = Some(vector),
+		}
+	}
+}
 
+/// Largest size the image of `source_size` can be seen at on the canvas, `None` if that's not
+/// less than its own size. Both sides are scaled alike, keeping the aspect ratio.
+fn max_display_size(
+	source_size: (u32, u32),
+	params: &ImageParams,
+	canvas: iced::Size,
+) -> Option<(u32, u32)> {
+	let (width, height) = source_size;
+	let display_size = params.display_size(iced::Size::new(width as f32, height as f32), canvas);
+	// Parts sticking out of the canvas can't be seen. A stretched image needs the resolution of
+	// its more stretched side.
+	let factor = (display_size.width.min(canvas.width) / width.max(1) as f32)
+		.max(display_size.height.min(canvas.height) / height.max(1) as f32);
+	if factor >= 1.0 {
+		return None;
+	}
+	let scaled = |length: u32| ((length as f32 * factor).ceil() as u32).clamp(1, length);
+	Some((scaled(width), scaled(height)))
+}
+
+/// Size images are checked at, a common projector resolution
+const CHECK_CANVAS: iced::Size = iced::Size {
+	width: 1920.0,
+	height: 1080.0,
+};
+
+/// Loads the presentation the way it's shown and reports what's wrong with it, for the `check`
+/// command. Fails if anything is.
+pub fn check(path: &Path) -> Result<()> {
+	let runtime = tokio::runtime::Runtime::new()?;
+	let presentation =
+		runtime.block_on(load_from_file(path, ImageCache::default(), CHECK_CANVAS))?;
+
+	// Fonts are loaded along with the presentation, their errors are among the warnings
+	let mut problems = 0;
+	for warning in &presentation.warnings {
+		println!("{}", warning);
+		problems += 1;
+	}
+	for (location, image) in presentation.images() {
+		if let Some(ref error) = image.error {
+			println!(
+				"slide {}: {}: {}",
+				location.slide_idx + 1,
+				image.path,
+				error
+			);
+			problems += 1;
+		}
+	}
+
+	println!(
+		"{}: {} slides, {} problems",
+		path.display(),
+		presentation.slides.len(),
+		problems
+	);
+	if problems > 0 {
+		anyhow::bail!("{} has problems", path.display());
+	}
+	Ok(())
+}
+
+/// Writes the text of the slides as markdown, without params, for the `export` command
+pub fn export(path: &Path, output: Option<&Path>) -> Result<()> {
+	let runtime = tokio::runtime::Runtime::new()?;
+	let presentation = runtime.block_on(parse_file(path))?;
+	let outline = outline(&presentation);
+	match output {
+		Some(output) => std::fs::write(output, outline)
+			.with_context(|| format!("failed to write {}", output.display()))?,
+		None => print!("{}", outline),
+	}
+	Ok(())
+}
+
+pub fn outline(presentation: &Presentation) -> String {
+	let mut lines = Vec::new();
+	for (idx, slide) in presentation.slides.iter().enumerate() {
+		if idx > 0 {
+			lines.push(String::from("---"));
+			lines.push(String::new());
+		}
+		for node in &slide.nodes {
+			match node.kind {
+				SlideNode::Header(size, ref txt) => {
+					lines.push(format!("{} {}", "#".repeat(size as usize), txt))
+				}
+				SlideNode::Text(ref txt) => lines.push(txt.clone()),
+				SlideNode::UnnumberedList(ref items) => {
+					lines.extend(items.iter().map(|item| format!("- {}", item)))
+				}
+				SlideNode::NumberedList(ref items) => lines.extend(
+					items
+						.iter()
+						.enumerate()
+						.map(|(idx, item)| format!("{}. {}", idx + 1, item)),
+				),
+				SlideNode::Image(ref image) => {
+					lines.push(format!("![{}]({})", image.alt_text, image.path))
+				}
+				SlideNode::CodeBlock(language, _, ref code) => {
+					let language = match language {
+						Language::Rust => "rust",
+						Language::PlainText => "",
+					};
+					lines.push(format!("```{}", language));
+					lines.extend(code.trim_end().lines().map(String::from));
+					lines.push(String::from("```"));
+				}
+				SlideNode::Comment(ref txt) => lines.push(format!("<!-- {} -->", txt)),
+			}
+			lines.push(String::new());
+		}
+	}
+	lines.iter().map(|line| format!("{}\n", line)).collect()
+}
+
+/// Reads and parses the presentation, without loading fonts and images
+async fn parse_file(path: &Path) -> LoadResult {
 	let file = async_fs::read_to_string(path).await?;
 
 	let title = path
@@ -26,49 +325,408 @@ async fn load_from_file(path: &str) -> LoadFromArgsResult {
 		.map(|x| x.to_string_lossy().to_string())
 		.unwrap_or_else(|| env!("CARGO_PKG_NAME").to_string());
 
-	let presentation_dir = path
+	let canonical_path = path
 		.canonicalize()
 		.with_context(|| format!("failed to canonicalize {}", path.display()))?;
-	let presentation_dir = presentation_dir
+	let presentation_dir = canonical_path
 		.parent()
 		.ok_or(anyhow!("failed to get parent of {}", path.display()))?;
 
 	let mut presentation =
 		crate::parser::parse_presentation(title, presentation_dir.to_owned(), &file)?;
+	presentation.source = Some(path.to_owned());
+	presentation.canonical_source = Some(canonical_path);
+	Ok(presentation)
+}
+
+async fn load_from_file(path: &Path, image_cache: ImageCache, canvas: iced::Size) -> LoadResult {
+	let mut presentation = parse_file(path).await?;
+	let presentation_dir = presentation.path.clone();
+	let presentation_dir = presentation_dir.as_path();
+
+	let front_matter = &presentation.front_matter;
+	let warnings = &mut presentation.warnings;
+	let mut custom_fonts = Vec::new();
+	if let Some(font) =
+		load_custom_font(presentation_dir, &front_matter.header_font, warnings).await
+	{
+		presentation.fonts.header = font;
+		custom_fonts.push(font);
+	}
+	if let Some(font) = load_custom_font(presentation_dir, &front_matter.body_font, warnings).await
+	{
+		presentation.fonts.body = font;
+		custom_fonts.push(font);
+	}
+	if let Some(font) = load_custom_font(presentation_dir, &front_matter.code_font, warnings).await
+	{
+		presentation.fonts.code = Some(font);
+		custom_fonts.push(font);
+	}
+	let mut fallback_fonts = Vec::new();
+	for path in &front_matter.fallback_fonts {
+		if let Some(font) = load_custom_font(presentation_dir, &Some(path.clone()), warnings).await
+		{
+			fallback_fonts.push(font);
+		}
+	}
+	presentation.fonts.glyphs = glyph_coverage(&custom_fonts, &fallback_fonts);
 
 	let images = presentation
 		.slides
 		.iter_mut()
 		.flat_map(|slide| slide.nodes.iter_mut())
-		.filter_map(|node| match node {
+		.filter_map(|node| match node.kind {
 			SlideNode::Image(ref mut img) => Some(img),
 			_ => None,
 		});
 
+	// Decoding of all images is started before waiting for any of them, so they're decoded in
+	// parallel on the blocking thread pool
+	let mut pending = Vec::new();
+	let mut decodings = Decodings::default();
 	for image in images {
 		let path = presentation_dir.join(&image.path);
-		if !path.exists() || !path.is_file() {
+		if !path.is_file() {
 			log::error!("{} not found", path.display());
+			image.error = Some("file not found".to_string());
 			continue;
 		}
 
-		let dyn_image = tokio::task::spawn_blocking(move || image::open(path))
-			.await??
-			.into_bgra8();
-		let (width, height) = (dyn_image.width(), dyn_image.height());
-		let pixels = dyn_image.into_raw();
+		let key = match ImageKey::new(&path, &image.params, canvas).await {
+			Ok(v) => v,
+			Err(e) => {
+				log::error!("Failed to load {}: {:?}", path.display(), e);
+				image.error = Some(e.to_string());
+				continue;
+			}
+		};
+		decodings.start(path, &image.params, canvas, &key, &image_cache);
+		pending.push((image, key));
+	}
 
-		let handle = iced::image::Handle::from_pixels(width, height, pixels);
-		image.handle = Some(handle);
+	let decoded_images = decodings.finish().await;
+	for (image, key) in pending {
+		match decoded_images[&key] {
+			Ok(ref decoded) => decoded.clone().apply_to(image),
+			// Broken images don't fail the whole presentation
+			Err(ref e) => image.error = Some(e.clone()),
+		}
 	}
+	// Entries of images that are gone from the presentation are dropped along with the old map
+	*image_cache.0.lock().unwrap() = decoded_images
+		.into_iter()
+		.filter_map(|(key, decoded)| Some((key, decoded.ok()?)))
+		.collect();
 
 	Ok(presentation)
 }
 
+/// Each image comes with the path and params it was decoded for, the presentation can have
+/// changed by the time it's done
+pub type ReloadImagesResult = Vec<(ImageLocation, PathBuf, ImageParams, Result<DecodedImage>)>;
+
+/// Decodes the given images again, after their files changed or the canvas grew. Cached entries
+/// of the files are replaced, the rest of the cache is kept.
+pub async fn reload_images(
+	images: Vec<(ImageLocation, PathBuf, ImageParams)>,
+	image_cache: ImageCache,
+	canvas: iced::Size,
+) -> ReloadImagesResult {
+	let mut pending = Vec::new();
+	let mut decodings = Decodings::default();
+	for (location, path, params) in images {
+		if !path.is_file() {
+			log::error!("{} not found", path.display());
+			pending.push((location, path, params, Err(anyhow!("file not found"))));
+			continue;
+		}
+		let key = ImageKey::new(&path, &params, canvas).await;
+		if let Ok(ref key) = key {
+			decodings.start(path.clone(), &params, canvas, key, &image_cache);
+		}
+		pending.push((location, path, params, key));
+	}
+
+	let decoded_images = decodings.finish().await;
+	let results = pending
+		.into_iter()
+		.map(|(location, path, params, key)| {
+			let decoded = key.and_then(|key| decoded_images[&key].clone().map_err(|e| anyhow!(e)));
+			(location, path, params, decoded)
+		})
+		.collect();
+
+	// Failed files are dropped from the cache too
+	let reloaded_paths = decoded_images
+		.keys()
+		.map(|key| key.path.clone())
+		.collect::<HashSet<_>>();
+	let mut image_cache = image_cache.0.lock().unwrap();
+	image_cache.retain(|key, _| !reloaded_paths.contains(&key.path));
+	image_cache.extend(
+		decoded_images
+			.into_iter()
+			.filter_map(|(key, decoded)| Some((key, decoded.ok()?))),
+	);
+	results
+}
+
+fn is_svg(path: &Path) -> bool {
+	path.extension()
+		.map(|ext| ext.eq_ignore_ascii_case("svg") || ext.eq_ignore_ascii_case("svgz"))
+		.unwrap_or(false)
+}
+
+/// Only the size is read here, rasterizing is left to the renderer so it happens at the size the
+/// image is drawn at
+fn load_vector_image(path: &Path) -> Result<VectorImage> {
+	let data = std::fs::read(path)?;
+	let tree = usvg::Tree::from_data(&data, &usvg::Options::default())
+		.with_context(|| format!("failed to parse {}", path.display()))?;
+	let size = tree.svg_node().size;
+
+	Ok(VectorImage {
+		handle: iced::svg::Handle::from_memory(data),
+		size: iced::Size::new(size.width() as f32, size.height() as f32),
+	})
+}
+
+/// Decodes the image, applying params that change its pixels rather than how it's laid out.
+/// Animated GIFs and PNGs get all of their frames decoded.
+/// Returns the size of the cropped source image along with the decoded frames, which are
+/// downscaled to what can be seen of them on the `canvas`.
+fn decode_image(
+	path: &Path,
+	params: &ImageParams,
+	canvas: Option<iced::Size>,
+) -> Result<(iced::image::Handle, Option<Animation>, iced::Size)> {
+	let to_handle = |mut rgba: image::RgbaImage| -> Result<_> {
+		if let Some(crop) = params.crop {
+			// crop_imm clamps the rectangle to the image instead of failing
+			let fits = |start: u32, length: u32, image_length: u32| {
+				start
+					.checked_add(length)
+					.map_or(false, |end| end <= image_length)
+			};
+			if !fits(crop.x, crop.width, rgba.width()) || !fits(crop.y, crop.height, rgba.height())
+			{
+				anyhow::bail!(
+					"Crop {} {} {} {} is outside of the {}x{} image",
+					crop.x,
+					crop.y,
+					crop.width,
+					crop.height,
+					rgba.width(),
+					rgba.height()
+				);
+			}
+			rgba = image::imageops::crop_imm(&rgba, crop.x, crop.y, crop.width, crop.height)
+				.to_image();
+		}
+		let source_size = iced::Size::new(rgba.width() as f32, rgba.height() as f32);
+		// Corner radius is in source pixels, so corners are rounded before downscaling
+		if let Some(corner_radius) = params.corner_radius {
+			round_corners(&mut rgba, corner_radius);
+		}
+		let max_size =
+			canvas.and_then(|canvas| max_display_size(rgba.dimensions(), params, canvas));
+		if let Some((width, height)) = max_size {
+			let filter = image::imageops::FilterType::CatmullRom;
+			rgba = image::imageops::resize(&rgba, width, height, filter);
+		}
+
+		let bgra = image::DynamicImage::ImageRgba8(rgba).into_bgra8();
+		let (width, height) = bgra.dimensions();
+		let handle = iced::image::Handle::from_pixels(width, height, bgra.into_raw());
+		Ok((handle, source_size))
+	};
+
+	let mut frames = match decode_animation_frames(path)? {
+		Some(frames) => frames,
+		None => {
+			let (handle, source_size) = to_handle(image::open(path)?.into_rgba8())?;
+			return Ok((handle, None, source_size));
+		}
+	};
+	if frames.len() == 1 {
+		let (handle, source_size) = to_handle(frames.remove(0).into_buffer())?;
+		return Ok((handle, None, source_size));
+	}
+
+	let mut source_size = iced::Size::ZERO;
+	let frames = frames
+		.into_iter()
+		.map(|frame| {
+			let delay = frame_delay(&frame);
+			let (handle, frame_size) = to_handle(frame.into_buffer())?;
+			source_size = frame_size;
+			Ok((handle, delay))
+		})
+		.collect::<Result<Vec<_>>>()?;
+	let first_frame = frames[0].0.clone();
+	Ok((first_frame, Some(Animation::new(frames)), source_size))
+}
+
+/// `None` for formats that can't be animated and PNGs that aren't APNGs
+fn decode_animation_frames(path: &Path) -> Result<Option<Vec<image::Frame>>> {
+	use image::AnimationDecoder;
+
+	let reader = || -> Result<_> { Ok(std::io::BufReader::new(std::fs::File::open(path)?)) };
+	let frames = match image::ImageFormat::from_path(path) {
+		Ok(image::ImageFormat::Gif) => image::codecs::gif::GifDecoder::new(reader()?)?
+			.into_frames()
+			.collect_frames()?,
+		Ok(image::ImageFormat::Png) => {
+			let decoder = image::codecs::png::PngDecoder::new(reader()?)?;
+			if !decoder.is_apng() {
+				return Ok(None);
+			}
+			decoder.apng().into_frames().collect_frames()?
+		}
+		_ => return Ok(None),
+	};
+	if frames.is_empty() {
+		return Ok(None);
+	}
+	Ok(Some(frames))
+}
+
+/// Browsers show frames with delays of 10ms or less for 100ms, many GIFs rely on that
+fn frame_delay(frame: &image::Frame) -> Duration {
+	let (numerator, denominator) = frame.delay().numer_denom_ms();
+	let delay = Duration::from_secs_f64(f64::from(numerator) / f64::from(denominator.max(1)));
+	if delay <= Duration::from_millis(10) {
+		Duration::from_millis(100)
+	} else {
+		delay
+	}
+}
+
+/// Makes pixels outside of the rounded corners transparent
+fn round_corners(image: &mut image::RgbaImage, radius: f32) {
+	let (width, height) = (image.width() as f32, image.height() as f32);
+	let radius = radius.min(width / 2.0).min(height / 2.0);
+
+	for (x, y, pixel) in image.enumerate_pixels_mut() {
+		let (x, y) = (x as f32 + 0.5, y as f32 + 0.5);
+		// Distance from the center of the nearest corner circle, 0 outside of the corners
+		let dx = (radius - x).max(x - (width - radius)).max(0.0);
+		let dy = (radius - y).max(y - (height - radius)).max(0.0);
+		let distance = (dx * dx + dy * dy).sqrt();
+
+		// Edge is antialiased over one pixel
+		let coverage = (radius - distance + 0.5).clamp(0.0, 1.0);
+		if coverage < 1.0 {
+			pixel[3] = (f32::from(pixel[3]) * coverage) as u8;
+		}
+	}
+}
+
+/// Falls back to the embedded font (by returning `None`) when the font is missing or invalid
+/// Fonts that fail to load are added to the `warnings` of the presentation
+async fn load_custom_font(
+	presentation_dir: &Path,
+	path: &Option<String>,
+	warnings: &mut Vec<String>,
+) -> Option<iced::Font> {
+	let relative_path = path.as_ref()?;
+	let path = presentation_dir.join(relative_path);
+	match load_font(&path).await {
+		Ok(font) => Some(font),
+		Err(e) => {
+			error!("Failed to load font {}: {:?}", path.display(), e);
+			warnings.push(format!("{}: {}", relative_path, e));
+			None
+		}
+	}
+}
+
+struct LoadedFont {
+	path: PathBuf,
+	modified: Option<SystemTime>,
+	font: iced::Font,
+}
+
+/// iced requires font data to be `'static`, so loaded fonts are leaked. They're cached by path
+/// and modification time so that reloading the presentation doesn't leak them again.
+static LOADED_FONTS: Mutex<Vec<LoadedFont>> = Mutex::new(Vec::new());
+
+async fn load_font(path: &Path) -> Result<iced::Font> {
+	let modified = async_fs::metadata(path).await?.modified().ok();
+
+	if let Some(loaded_font) = LOADED_FONTS
+		.lock()
+		.unwrap()
+		.iter()
+		.find(|loaded_font| loaded_font.path == path && loaded_font.modified == modified)
+	{
+		return Ok(loaded_font.font);
+	}
+
+	let bytes = async_fs::read(path).await?;
+	if let Err(e) = ttf_parser::Face::from_slice(&bytes, 0) {
+		anyhow::bail!("not a valid TrueType or OpenType font: {}", e);
+	}
+
+	// Font name is used by iced to cache glyphs, so it has to change along with the file
+	let name = format!("{}@{:?}", path.display(), modified);
+	let font = iced::Font::External {
+		name: Box::leak(name.into_boxed_str()),
+		bytes: Box::leak(bytes.into_boxed_slice()),
+	};
+
+	LOADED_FONTS.lock().unwrap().push(LoadedFont {
+		path: path.to_owned(),
+		modified,
+		font,
+	});
+
+	Ok(font)
+}
+
+/// Coverages of the custom fonts presentations were loaded with. Leaked like the fonts, they're
+/// reused when a presentation is reloaded with the same fonts.
+static GLYPH_COVERAGES: Mutex<Vec<&'static GlyphCoverage>> = Mutex::new(Vec::new());
+
+/// Coverage of the embedded fonts and the `custom` ones, with the `fallback` fonts tried before
+/// the bundled one
+fn glyph_coverage(custom: &[iced::Font], fallback: &[iced::Font]) -> &'static GlyphCoverage {
+	if custom.is_empty() && fallback.is_empty() {
+		return GlyphCoverage::embedded();
+	}
+	let fonts = views::fonts::EMBEDDED
+		.iter()
+		.chain(custom)
+		.copied()
+		.collect::<Vec<_>>();
+	let fallback = fallback
+		.iter()
+		.copied()
+		.chain([views::fonts::DEJAVU_SANS])
+		.collect::<Vec<_>>();
+
+	let mut coverages = GLYPH_COVERAGES.lock().unwrap();
+	let names = fonts.iter().chain(&fallback).map(|font| font_name(*font));
+	if let Some(coverage) = coverages
+		.iter()
+		.find(|coverage| coverage.fonts().map(font_name).eq(names.clone()))
+	{
+		return coverage;
+	}
+	let coverage: &'static GlyphCoverage =
+		Box::leak(Box::new(GlyphCoverage::new(&fonts, &fallback)));
+	coverages.push(coverage);
+	coverage
+}
+
 pub type StartFileWatcherResult = Option<FileWatch>;
 
-pub async fn start_file_watcher(path: PathBuf) -> StartFileWatcherResult {
-	match FileWatch::new(path).await {
+pub async fn start_file_watcher(files: Vec<PathBuf>) -> StartFileWatcherResult {
+	if files.is_empty() {
+		return None;
+	}
+	match FileWatch::new(files).await {
 		Ok(v) => Some(v),
 		Err(e) => {
 			error!("Failed to crate file watcher: {:?}", e);
@@ -77,7 +735,7 @@ pub async fn start_file_watcher(path: PathBuf) -> StartFileWatcherResult {
 	}
 }
 
-async fn load_example() -> LoadFromArgsResult {
+async fn load_example() -> LoadResult {
 	let slides = vec![
 		Slide {
 			nodes: vec![
@@ -86,8 +744,9 @@ async fn load_example() -> LoadFromArgsResult {
 					String::from(
 						"Wprowadzenie do Rusta dla tych, którzy już trochę programować umieją",
 					),
-				),
-				SlideNode::Text(String::from("Maciej Sołtys")),
+				)
+				.into(),
+				SlideNode::Text(String::from("Maciej Sołtys")).into(),
 				// SlideNode::Header(HeaderSize::Four, String::from("Maciej Sołtys")),
 			],
 			..Default::default()
@@ -97,25 +756,27 @@ async fn load_example() -> LoadFromArgsResult {
 				SlideNode::Header(
 					HeaderSize::Three,
 					String::from("Wersja dla tych, którzy umieją, czyli"),
-				),
+				)
+				.into(),
 				SlideNode::UnnumberedList(vec![
 					String::from("Szybki przegląd składni, typów"),
 					String::from("Feature'y"),
 					String::from("Różnice (C++ / Java / C# / Go)"),
-				]),
+				])
+				.into(),
 			],
 			..Default::default()
 		},
 		Slide {
 			nodes: vec![
-				SlideNode::Header(HeaderSize::One, String::from("Ferris")),
-				SlideNode::Image(load_image("ferris.png").await?),
+				SlideNode::Header(HeaderSize::One, String::from("Ferris")).into(),
+				SlideNode::Image(load_image("ferris.png").await?).into(),
 			],
 			..Default::default()
 		},
 		Slide {
 			nodes: vec![
-				SlideNode::Header(HeaderSize::Two, String::from("while loop")),
+				SlideNode::Header(HeaderSize::Two, String::from("while loop")).into(),
 				SlideNode::CodeBlock(
 					Language::Rust,
 					CodeBlockParams::default(),
@@ -126,13 +787,14 @@ while a < 10 {
 	a += 1;
 }"#,
 					),
-				),
+				)
+				.into(),
 			],
 			..Default::default()
 		},
 		Slide {
 			nodes: vec![
-				SlideNode::Header(HeaderSize::Two, String::from("enum")),
+				SlideNode::Header(HeaderSize::Two, String::from("enum")).into(),
 				SlideNode::CodeBlock(
 					Language::Rust,
 					CodeBlockParams::default(),
@@ -156,7 +818,8 @@ fn sqrt(n: f64) -> SqrtResult {
 	Ok(sqrt_result)
 }"#,
 					),
-				),
+				)
+				.into(),
 			],
 			..Default::default()
 		},
@@ -165,25 +828,31 @@ fn sqrt(n: f64) -> SqrtResult {
 	let presentation = Presentation {
 		title: "Example presentation".to_string(),
 		path: PathBuf::from("."),
+		source: None,
+		canonical_source: None,
+		front_matter: Default::default(),
+		fonts: Default::default(),
 		slides,
+		warnings: Vec::new(),
 	};
 	Ok(presentation)
 }
 
 async fn load_image(path: &str) -> Result<Image> {
-	let img_path = format!("assets/{}", path);
-	let dyn_image = tokio::task::spawn_blocking(move || image::open(img_path))
-		.await??
-		.into_bgra8();
-	let (width, height) = (dyn_image.width(), dyn_image.height());
-	let pixels = dyn_image.into_raw();
-
-	let handle: Option<_> = iced::image::Handle::from_pixels(width, height, pixels).into();
+	let img_path = PathBuf::from(format!("assets/{}", path));
+	let (handle, animation, _) =
+		tokio::task::spawn_blocking(move || decode_image(&img_path, &Default::default(), None))
+			.await??;
+	let handle = Some(handle);
 
 	Ok(Image {
 		path: path.to_string(),
 		alt_text: "Ferris the crab".to_string(),
 		params: Default::default(),
 		handle,
+		animation,
+		vector: None,
+		source_size: None,
+		error: None,
 	})
 }
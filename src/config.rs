@@ -0,0 +1,547 @@
+use iced::keyboard::{KeyCode, Modifiers};
+use log::*;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// Directory of the user configuration, e.g. `~/.config/prodzytsol`
+pub fn config_dir() -> Option<PathBuf> {
+	let base = std::env::var_os("XDG_CONFIG_HOME")
+		.filter(|v| !v.is_empty())
+		.map(PathBuf::from)
+		.or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))
+		.or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+	Some(base.join(env!("CARGO_PKG_NAME")))
+}
+
+/// Directory of data kept between runs, e.g. `~/.local/share/prodzytsol`
+pub fn data_dir() -> Option<PathBuf> {
+	let base = std::env::var_os("XDG_DATA_HOME")
+		.filter(|v| !v.is_empty())
+		.map(PathBuf::from)
+		.or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))
+		.or_else(|| {
+			std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share"))
+		})?;
+	Some(base.join(env!("CARGO_PKG_NAME")))
+}
+
+/// User preferences from `settings` in the config directory, as `key: value` lines
+#[derive(Debug, Clone)]
+pub struct Settings {
+	/// Clicking and scrolling changes slides
+	pub mouse_navigation: bool,
+}
+
+impl Default for Settings {
+	fn default() -> Self {
+		Settings {
+			mouse_navigation: true,
+		}
+	}
+}
+
+impl Settings {
+	pub fn load() -> Self {
+		match read_config_file("settings") {
+			Some(config) => Settings::parse(&config),
+			None => Default::default(),
+		}
+	}
+
+	pub fn parse(config: &str) -> Self {
+		let mut settings = Settings::default();
+		for (key, value) in config_lines(config) {
+			match key {
+				"mouse_navigation" => match value.parse() {
+					Ok(v) => settings.mouse_navigation = v,
+					Err(_) => warn!("Invalid value \"{}\" of \"{}\"", value, key),
+				},
+				_ => warn!("Unknown setting \"{}\"", key),
+			}
+		}
+		settings
+	}
+}
+
+fn read_config_file(name: &str) -> Option<String> {
+	let path = config_dir()?.join(name);
+	match std::fs::read_to_string(&path) {
+		Ok(config) => {
+			info!("Loading {}", path.display());
+			Some(config)
+		}
+		Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+		Err(e) => {
+			error!("Failed to read {}: {:?}", path.display(), e);
+			None
+		}
+	}
+}
+
+/// `key: value` pairs of the config, skipping empty lines and `#` comments
+fn config_lines(config: &str) -> impl Iterator<Item = (&str, &str)> {
+	config
+		.lines()
+		.map(str::trim)
+		.filter(|line| !line.is_empty() && !line.starts_with('#'))
+		.filter_map(|line| match line.split_once(':') {
+			Some((key, value)) => Some((key.trim(), value.trim())),
+			None => {
+				warn!("Invalid config line \"{}\", expected `key: value`", line);
+				None
+			}
+		})
+}
+
+/// What a key binding does
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Action {
+	Next,
+	Previous,
+	First,
+	Last,
+	/// Returns to the slide shown before the last jump
+	JumpBack,
+	ToggleFullscreen,
+	ExitFullscreen,
+	Overview,
+	/// Toggles a black screen, navigating brings the slide back
+	Blackout,
+	Whiteout,
+	/// Asks for another presentation to show
+	Open,
+	/// Move the selection of the overview grid and of the welcome screen
+	SelectLeft,
+	SelectRight,
+	SelectUp,
+	SelectDown,
+	/// Opens the selected slide or presentation, jumps to the typed slide number or resumes at
+	/// the slide the presentation was left at
+	Confirm,
+	/// Closes the overview
+	Close,
+	/// Forgets the selected recent presentation on the welcome screen
+	Remove,
+}
+
+impl Action {
+	pub fn context(self) -> KeyContext {
+		use Action::*;
+		match self {
+			SelectLeft | SelectRight | SelectUp | SelectDown | Confirm | Close | Remove => {
+				KeyContext::Selection
+			}
+			_ => KeyContext::Slides,
+		}
+	}
+}
+
+/// Where key bindings apply, the same key can do something else in each of them
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum KeyContext {
+	Slides,
+	/// Overview grid, welcome screen and what waits for a confirmation, like a typed slide number
+	Selection,
+}
+
+impl FromStr for Action {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		use Action::*;
+		Ok(match s {
+			"next" => Next,
+			"previous" => Previous,
+			"first" => First,
+			"last" => Last,
+			"jump_back" => JumpBack,
+			"fullscreen" => ToggleFullscreen,
+			"exit_fullscreen" => ExitFullscreen,
+			"overview" => Overview,
+			"blackout" => Blackout,
+			"whiteout" => Whiteout,
+			"open" => Open,
+			"select_left" => SelectLeft,
+			"select_right" => SelectRight,
+			"select_up" => SelectUp,
+			"select_down" => SelectDown,
+			"confirm" => Confirm,
+			"close" => Close,
+			"remove" => Remove,
+			_ => anyhow::bail!("Unknown action {}", s),
+		})
+	}
+}
+
+/// Key with the modifiers that have to be held, written like `Ctrl+Shift+N`
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct KeyBinding {
+	key_code: KeyCode,
+	shift: bool,
+	control: bool,
+	alt: bool,
+	logo: bool,
+}
+
+impl KeyBinding {
+	pub fn new(key_code: KeyCode, modifiers: Modifiers) -> Self {
+		KeyBinding {
+			key_code,
+			shift: modifiers.shift,
+			control: modifiers.control,
+			alt: modifiers.alt,
+			logo: modifiers.logo,
+		}
+	}
+
+	fn key(key_code: KeyCode) -> Self {
+		KeyBinding::new(key_code, Modifiers::default())
+	}
+}
+
+impl FromStr for KeyBinding {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let mut parts = s.split('+').map(str::trim).collect::<Vec<_>>();
+		let key = match parts.pop() {
+			Some(key) if !key.is_empty() => key,
+			// `+` itself is written as `Plus`
+			_ => anyhow::bail!("Missing key in {}", s),
+		};
+
+		let mut binding = KeyBinding::key(parse_key_code(key)?);
+		for modifier in parts {
+			match modifier.to_ascii_lowercase().as_str() {
+				"shift" => binding.shift = true,
+				"ctrl" | "control" => binding.control = true,
+				"alt" => binding.alt = true,
+				"logo" | "super" | "cmd" => binding.logo = true,
+				_ => anyhow::bail!("Unknown modifier {}", modifier),
+			}
+		}
+		Ok(binding)
+	}
+}
+
+impl std::fmt::Display for KeyBinding {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		let modifiers = [
+			(self.control, "Ctrl"),
+			(self.shift, "Shift"),
+			(self.alt, "Alt"),
+			(self.logo, "Logo"),
+		];
+		for (_, name) in modifiers.iter().filter(|(held, _)| *held) {
+			write!(f, "{}+", name)?;
+		}
+		// Key code names are the ones parsed, apart from digits
+		let key = format!("{:?}", self.key_code);
+		write!(f, "{}", key.strip_prefix("Key").unwrap_or(&key))
+	}
+}
+
+fn parse_key_code(key: &str) -> anyhow::Result<KeyCode> {
+	use KeyCode::*;
+
+	const LETTERS: [KeyCode; 26] = [
+		A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+	];
+	const DIGITS: [KeyCode; 10] = [Key0, Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9];
+	const FUNCTION_KEYS: [KeyCode; 12] = [F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12];
+
+	let lowercase = key.to_ascii_lowercase();
+	if let [letter @ b'a'..=b'z'] = lowercase.as_bytes() {
+		return Ok(LETTERS[usize::from(letter - b'a')]);
+	}
+	if let [digit @ b'0'..=b'9'] = lowercase.as_bytes() {
+		return Ok(DIGITS[usize::from(digit - b'0')]);
+	}
+	if let Some(n) = lowercase
+		.strip_prefix('f')
+		.and_then(|n| n.parse::<usize>().ok())
+		.filter(|n| (1..=FUNCTION_KEYS.len()).contains(n))
+	{
+		return Ok(FUNCTION_KEYS[n - 1]);
+	}
+
+	Ok(match lowercase.as_str() {
+		"space" => Space,
+		"enter" | "return" => Enter,
+		"numpadenter" => NumpadEnter,
+		"escape" | "esc" => Escape,
+		"tab" => Tab,
+		"backspace" => Backspace,
+		"delete" => Delete,
+		"insert" => Insert,
+		"left" => Left,
+		"right" => Right,
+		"up" => Up,
+		"down" => Down,
+		"home" => Home,
+		"end" => End,
+		"pageup" => PageUp,
+		"pagedown" => PageDown,
+		"period" | "." => Period,
+		"comma" | "," => Comma,
+		"minus" | "-" => Minus,
+		"plus" => Plus,
+		"equals" | "=" => Equals,
+		"slash" | "/" => Slash,
+		"semicolon" | ";" => Semicolon,
+		"apostrophe" | "'" => Apostrophe,
+		"grave" | "`" => Grave,
+		"lbracket" | "[" => LBracket,
+		"rbracket" | "]" => RBracket,
+		"backslash" | "\\" => Backslash,
+		_ => anyhow::bail!("Unknown key {}", key),
+	})
+}
+
+#[derive(Debug, Clone)]
+pub struct KeyBindings {
+	bindings: HashMap<(KeyContext, KeyBinding), Action>,
+}
+
+impl Default for KeyBindings {
+	fn default() -> Self {
+		use Action::*;
+		use KeyCode::*;
+
+		let defaults: &[(Action, &[KeyCode])] = &[
+			(Next, &[Space, X, Right, PageDown]),
+			(Previous, &[Backspace, Z, Left, PageUp]),
+			(First, &[Home]),
+			(Last, &[End]),
+			(JumpBack, &[H]),
+			(ToggleFullscreen, &[F]),
+			(ExitFullscreen, &[Escape]),
+			(Overview, &[O, Tab]),
+			(Blackout, &[B, Period]),
+			(Whiteout, &[W]),
+			(SelectLeft, &[Left]),
+			(SelectRight, &[Right]),
+			(SelectUp, &[Up]),
+			(SelectDown, &[Down]),
+			(Confirm, &[Enter, NumpadEnter]),
+			(Close, &[O, Tab, Escape]),
+			(Remove, &[Delete]),
+		];
+		let mut bindings = defaults
+			.iter()
+			.flat_map(|(action, key_codes)| {
+				key_codes
+					.iter()
+					.map(move |key_code| ((action.context(), KeyBinding::key(*key_code)), *action))
+			})
+			.collect::<HashMap<_, _>>();
+		let ctrl = Modifiers {
+			control: true,
+			..Default::default()
+		};
+		bindings.insert((Open.context(), KeyBinding::new(O, ctrl)), Open);
+		KeyBindings { bindings }
+	}
+}
+
+impl KeyBindings {
+	/// Defaults with the user's bindings from `keybindings` in the config directory applied
+	pub fn load() -> Self {
+		match read_config_file("keybindings") {
+			Some(config) => KeyBindings::parse(&config),
+			None => Default::default(),
+		}
+	}
+
+	/// Parses `action: key, key` lines, e.g. `next: Space, Ctrl+N`. Listed actions lose their
+	/// default keys. Invalid lines and keys bound to more than one action of a context are
+	/// reported.
+	pub fn parse(config: &str) -> Self {
+		let mut key_bindings = KeyBindings::default();
+		let mut user_bindings = HashMap::<(KeyContext, KeyBinding), Action>::new();
+
+		for (action, keys) in config_lines(config) {
+			let action = match action.parse::<Action>() {
+				Ok(v) => v,
+				Err(e) => {
+					warn!("{}", e);
+					continue;
+				}
+			};
+
+			key_bindings.bindings.retain(|_, bound| *bound != action);
+			for key in keys.split(',').map(str::trim).filter(|key| !key.is_empty()) {
+				let binding = match key.parse::<KeyBinding>() {
+					Ok(v) => v,
+					Err(e) => {
+						warn!("Invalid key \"{}\" for {:?}: {}", key, action, e);
+						continue;
+					}
+				};
+				match user_bindings.insert((action.context(), binding), action) {
+					Some(previous) if previous != action => warn!(
+						"{} is bound to both {:?} and {:?}, using {:?}",
+						key, previous, action, action
+					),
+					_ => (),
+				}
+			}
+		}
+
+		for ((context, binding), action) in user_bindings {
+			if let Some(default) = key_bindings.bindings.insert((context, binding), action) {
+				if default != action {
+					warn!(
+						"{:?} replaces the default {:?} binding of {:?}",
+						action, default, binding.key_code
+					);
+				}
+			}
+		}
+		key_bindings
+	}
+
+	/// Shortest of the keys bound to the action, for hints like `Press Enter to ...`
+	pub fn key_name(&self, action: Action) -> Option<String> {
+		self.bindings
+			.iter()
+			.filter(|(_, bound)| **bound == action)
+			.map(|((_, binding), _)| binding.to_string())
+			.min_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)))
+	}
+
+	/// Action of the key in the `context`. Keys bound without modifiers also work while
+	/// modifiers are held, unless that combination is bound to something else.
+	pub fn action(
+		&self,
+		context: KeyContext,
+		key_code: KeyCode,
+		modifiers: Modifiers,
+	) -> Option<Action> {
+		self.bindings
+			.get(&(context, KeyBinding::new(key_code, modifiers)))
+			.or_else(|| self.bindings.get(&(context, KeyBinding::key(key_code))))
+			.copied()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parse_key_binding() -> anyhow::Result<()> {
+		assert_eq!(
+			"Ctrl+Shift+N".parse::<KeyBinding>()?,
+			KeyBinding {
+				key_code: KeyCode::N,
+				shift: true,
+				control: true,
+				alt: false,
+				logo: false,
+			}
+		);
+		assert_eq!("f5".parse::<KeyBinding>()?, KeyBinding::key(KeyCode::F5));
+		assert_eq!("7".parse::<KeyBinding>()?, KeyBinding::key(KeyCode::Key7));
+		assert_eq!(
+			"PageDown".parse::<KeyBinding>()?,
+			KeyBinding::key(KeyCode::PageDown)
+		);
+		assert!("Hyper+N".parse::<KeyBinding>().is_err());
+		assert!("Ctrl+".parse::<KeyBinding>().is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn parse_key_bindings() {
+		let key_bindings = KeyBindings::parse(
+			r#"
+# comments and unknown actions are skipped
+next: N, Ctrl+Right
+overview: X
+unknown_action: Q
+"#,
+		);
+		let action =
+			|key_code, modifiers| key_bindings.action(KeyContext::Slides, key_code, modifiers);
+		let no_modifiers = Modifiers::default();
+		let ctrl = Modifiers {
+			control: true,
+			..Default::default()
+		};
+
+		assert_eq!(action(KeyCode::N, no_modifiers), Some(Action::Next));
+		assert_eq!(action(KeyCode::Right, ctrl), Some(Action::Next));
+		// Defaults of listed actions are replaced
+		assert_eq!(action(KeyCode::Space, no_modifiers), None);
+		assert_eq!(action(KeyCode::X, no_modifiers), Some(Action::Overview));
+		assert_eq!(action(KeyCode::Left, no_modifiers), Some(Action::Previous));
+		assert_eq!(action(KeyCode::Q, no_modifiers), None);
+		// Defaults with modifiers are kept apart from the same key without them
+		assert_eq!(action(KeyCode::O, no_modifiers), Some(Action::Overview));
+		assert_eq!(action(KeyCode::O, ctrl), Some(Action::Open));
+		// Keys bound without modifiers work with them held
+		let shift = Modifiers {
+			shift: true,
+			..Default::default()
+		};
+		assert_eq!(action(KeyCode::N, shift), Some(Action::Next));
+		assert_eq!(action(KeyCode::Left, ctrl), Some(Action::Previous));
+	}
+
+	#[test]
+	fn parse_selection_key_bindings() {
+		let key_bindings = KeyBindings::parse(
+			r#"
+select_left: H
+confirm: Space, Enter
+previous: H
+"#,
+		);
+		let action =
+			|context, key_code| key_bindings.action(context, key_code, Modifiers::default());
+
+		// Contexts don't share keys
+		assert_eq!(
+			action(KeyContext::Selection, KeyCode::H),
+			Some(Action::SelectLeft)
+		);
+		assert_eq!(
+			action(KeyContext::Slides, KeyCode::H),
+			Some(Action::Previous)
+		);
+		assert_eq!(action(KeyContext::Selection, KeyCode::Left), None);
+		assert_eq!(action(KeyContext::Slides, KeyCode::Left), None);
+		assert_eq!(
+			action(KeyContext::Selection, KeyCode::Space),
+			Some(Action::Confirm)
+		);
+		assert_eq!(
+			action(KeyContext::Slides, KeyCode::Space),
+			Some(Action::Next)
+		);
+		assert_eq!(action(KeyContext::Selection, KeyCode::NumpadEnter), None);
+		assert_eq!(
+			action(KeyContext::Selection, KeyCode::Escape),
+			Some(Action::Close)
+		);
+	}
+
+	#[test]
+	fn key_names() {
+		let defaults = KeyBindings::default();
+		assert_eq!(defaults.key_name(Action::Open), Some("Ctrl+O".to_string()));
+		assert_eq!(
+			defaults.key_name(Action::Confirm),
+			Some("Enter".to_string())
+		);
+
+		let key_bindings = KeyBindings::parse("open: Ctrl+Shift+7\nconfirm:\n");
+		assert_eq!(
+			key_bindings.key_name(Action::Open),
+			Some("Ctrl+Shift+7".to_string())
+		);
+		assert_eq!(key_bindings.key_name(Action::Confirm), None);
+	}
+}
@@ -6,22 +6,52 @@ use iced_native::event::Status;
 use iced_native::Event;
 use log::*;
 use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashSet;
 use std::fmt::{Debug, Formatter};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, Instant};
 use tokio_stream::wrappers::BroadcastStream;
 use tokio_stream::StreamExt;
 
+mod cli;
 mod commands;
+mod config;
 mod parser;
+mod recent;
 mod views;
 
 #[cfg(test)]
 mod tests;
 
 fn main() -> Result<()> {
-	flexi_logger::Logger::with_env_or_str(concat!(env!("CARGO_PKG_NAME"), "=debug")).start()?;
-	App::run(Settings::default())?;
+	let command_line = match cli::Cli::parse(std::env::args_os().skip(1)) {
+		Ok(v) => v,
+		Err(e) => {
+			eprintln!("{}\n\nSee {} --help", e, env!("CARGO_PKG_NAME"));
+			std::process::exit(2);
+		}
+	};
+
+	let logger = match command_line.log_level {
+		Some(ref level) => {
+			flexi_logger::Logger::with_str(format!("{}={}", env!("CARGO_PKG_NAME"), level))
+		}
+		None => flexi_logger::Logger::with_env_or_str(concat!(env!("CARGO_PKG_NAME"), "=debug")),
+	};
+	logger.start()?;
+
+	match command_line.subcommand {
+		cli::Subcommand::Present(options) => App::run(Settings {
+			// Saving the last slide is waited for first
+			exit_on_close_request: false,
+			..Settings::with_flags(options)
+		})?,
+		cli::Subcommand::Check(path) => commands::check(&path)?,
+		cli::Subcommand::Export { path, output } => commands::export(&path, output.as_deref())?,
+		cli::Subcommand::Help => print!("{}", cli::usage()),
+	}
 
 	Ok(())
 }
@@ -29,10 +59,28 @@ fn main() -> Result<()> {
 pub struct App {
 	stage: Stage,
 	mode: Mode,
+	/// Presentation file that is shown and reloaded, the example is shown without one
+	path: Option<PathBuf>,
+	/// Window size
+	viewport: Size,
+	image_cache: commands::ImageCache,
+	/// Size images were last loaded for
+	canvas: Size,
+	key_bindings: config::KeyBindings,
+	settings: config::Settings,
+	wheel: WheelNavigation,
+	recent: recent::RecentPresentations,
+	last_slides: recent::LastSlides,
+	/// Given on the command line
+	options: cli::PresentOptions,
+	/// Slide of the current presentation stored in `last_slides`
+	saved_slide: Option<usize>,
+	/// Set when the window is closed, once the data files are written
+	exiting: bool,
 }
 
 pub enum Stage {
-	WelcomeScreen,
+	WelcomeScreen(Welcome),
 	Presentation {
 		presentation: Presentation,
 		state: PresentationState,
@@ -43,19 +91,938 @@ pub enum Stage {
 #[derive(Debug)]
 pub struct Presentation {
 	title: String,
+	/// Directory of the presentation, paths in it are relative to it
 	path: PathBuf,
+	/// File the presentation was loaded from, `None` for the built-in example
+	source: Option<PathBuf>,
+	/// Identifies the presentation across runs
+	canonical_source: Option<PathBuf>,
+	front_matter: FrontMatter,
+	fonts: Fonts,
 	slides: Vec<Slide>,
+	/// Problems that didn't stop loading it, like unknown attributes or fonts that failed to load
+	warnings: Vec<String>,
+}
+
+impl Presentation {
+	/// Custom fonts from the front matter, relative to the presentation
+	pub fn font_paths(&self) -> impl Iterator<Item = &String> {
+		let front_matter = &self.front_matter;
+		[
+			&front_matter.header_font,
+			&front_matter.body_font,
+			&front_matter.code_font,
+		]
+		.into_iter()
+		.flatten()
+		.chain(&front_matter.fallback_fonts)
+	}
+
+	/// Files that trigger a reload when they change: the presentation, its fonts and images
+	pub fn watched_files(&self) -> Vec<PathBuf> {
+		let source = match self.source {
+			Some(ref v) => v,
+			None => return Vec::new(),
+		};
+		let images = self.images().map(|(_, image)| &image.path);
+
+		let mut files = vec![source.clone()];
+		files.extend(
+			self.font_paths()
+				.chain(images)
+				.map(|path| self.path.join(path)),
+		);
+		files
+	}
+
+	/// Image nodes. Slide backgrounds aren't drawn, so they're left out.
+	pub fn images(&self) -> impl Iterator<Item = (ImageLocation, &Image)> {
+		self.slides
+			.iter()
+			.enumerate()
+			.flat_map(|(slide_idx, slide)| {
+				slide
+					.nodes
+					.iter()
+					.enumerate()
+					.filter_map(move |(node_idx, node)| match node.kind {
+						SlideNode::Image(ref image) => Some((
+							ImageLocation {
+								slide_idx,
+								node_idx,
+							},
+							image,
+						)),
+						_ => None,
+					})
+			})
+	}
+
+	pub fn image_mut(&mut self, location: ImageLocation) -> Option<&mut Image> {
+		match self
+			.slides
+			.get_mut(location.slide_idx)?
+			.nodes
+			.get_mut(location.node_idx)?
+			.kind
+		{
+			SlideNode::Image(ref mut image) => Some(image),
+			_ => None,
+		}
+	}
+
+	/// Images stored in the changed `files`, with their full path. `None` if some of the files
+	/// aren't images, which needs the whole presentation to be reloaded.
+	pub fn images_in(
+		&self,
+		files: &HashSet<PathBuf>,
+	) -> Option<Vec<(ImageLocation, PathBuf, ImageParams)>> {
+		let mut images = Vec::new();
+		let mut image_files = HashSet::new();
+		for (location, image) in self.images() {
+			let path = self.path.join(&image.path);
+			match watched_path(&path) {
+				Some(watched) if files.contains(&watched) => {
+					images.push((location, path, image.params.clone()));
+					image_files.insert(watched);
+				}
+				_ => (),
+			}
+		}
+		if image_files.len() == files.len() {
+			Some(images)
+		} else {
+			None
+		}
+	}
+
+	/// Raster images, which are decoded for the size of the canvas. SVGs are rasterized at the
+	/// size they're drawn at anyway.
+	pub fn raster_images(&self) -> Vec<(ImageLocation, PathBuf, ImageParams)> {
+		self.images()
+			.filter(|(_, image)| image.vector.is_none())
+			.map(|(location, image)| (location, self.path.join(&image.path), image.params.clone()))
+			.collect()
+	}
+
+	/// Transition used when entering the slide at `idx`. Slide params take precedence over the
+	/// front matter.
+	pub fn transition_for(&self, idx: usize) -> Transition {
+		let params = self.slides.get(idx).map(|slide| &slide.params);
+		Transition {
+			kind: params
+				.and_then(|params| params.transition)
+				.or(self.front_matter.transition)
+				.unwrap_or(TransitionKind::None),
+			duration: params
+				.and_then(|params| params.transition_duration)
+				.or(self.front_matter.transition_duration)
+				.unwrap_or(DEFAULT_TRANSITION_DURATION),
+		}
+	}
+
+	/// Index of the slide the one at `old_idx` of the `old` presentation became after the deck
+	/// was edited. Slides are matched by explicit id, then by header, then by content
+	/// similarity, preferring the closest one to `old_idx`. Otherwise `old_idx` is kept, within
+	/// the slide count.
+	pub fn find_slide(&self, old: &Presentation, old_idx: usize) -> usize {
+		let clamped_idx = old_idx.min(self.slides.len().saturating_sub(1));
+		let old_slide = match old.slides.get(old_idx) {
+			Some(v) => v,
+			None => return clamped_idx,
+		};
+		let distance = |idx: usize| (idx as isize - old_idx as isize).abs();
+		let closest = |matches: &dyn Fn(&Slide) -> bool| {
+			self.slides
+				.iter()
+				.enumerate()
+				.filter(|(_, slide)| matches(slide))
+				.map(|(idx, _)| idx)
+				.min_by_key(|idx| distance(*idx))
+		};
+
+		if let Some(id) = old_slide.id() {
+			if let Some(idx) = closest(&|slide| slide.id() == Some(id)) {
+				return idx;
+			}
+		}
+		if let Some(header) = old_slide.first_header() {
+			if let Some(idx) = closest(&|slide| slide.first_header() == Some(header)) {
+				return idx;
+			}
+		}
+		self.slides
+			.iter()
+			.enumerate()
+			.map(|(idx, slide)| (idx, slide.similarity(old_slide)))
+			.filter(|(_, similarity)| *similarity >= MIN_SLIDE_SIMILARITY)
+			.max_by(|(idx, similarity), (other_idx, other_similarity)| {
+				similarity
+					.partial_cmp(other_similarity)
+					.unwrap_or(std::cmp::Ordering::Equal)
+					.then_with(|| distance(*other_idx).cmp(&distance(*idx)))
+			})
+			.map(|(idx, _)| idx)
+			.unwrap_or(clamped_idx)
+	}
+
+	/// Whether the alt text is drawn as a caption below the image. Image params take precedence
+	/// over the front matter.
+	pub fn has_caption(&self, image: &Image) -> bool {
+		!image.alt_text.is_empty() && image.params.caption.unwrap_or(self.front_matter.captions)
+	}
+
+	/// Number of the first figure on the slide at `idx`, if figures are numbered. Every captioned
+	/// image is a figure.
+	pub fn first_figure_number(&self, idx: usize) -> Option<usize> {
+		if !self.front_matter.figure_numbers {
+			return None;
+		}
+		let previous_figures = self.slides[..idx.min(self.slides.len())]
+			.iter()
+			.flat_map(|slide| &slide.nodes)
+			.filter(
+				|node| matches!(node.kind, SlideNode::Image(ref image) if self.has_caption(image)),
+			)
+			.count();
+		Some(previous_figures + 1)
+	}
+}
+
+/// Slides sharing fewer words are considered different slides when re-anchoring after a reload
+const MIN_SLIDE_SIMILARITY: f32 = 0.5;
+
+/// Presentation wide settings, placed at the very beginning of the file between `+++` lines.
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct FrontMatter {
+	transition: Option<TransitionKind>,
+	transition_duration: Option<Duration>,
+	/// Whether to show the footer with slide number and progress bar
+	footer: bool,
+	/// Custom footer text. When unset, author, event and date are joined instead.
+	footer_text: Option<String>,
+	author: Option<String>,
+	event: Option<String>,
+	date: Option<String>,
+	/// Paths to TTF/OTF files, relative to the presentation file
+	header_font: Option<String>,
+	body_font: Option<String>,
+	code_font: Option<String>,
+	/// Fonts tried in order for characters missing from the primary font (emoji, CJK, symbols).
+	/// The bundled DejaVu Sans, which covers symbols but not emoji and CJK, is tried after them.
+	fallback_fonts: Vec<String>,
+	align: Option<HorizontalAlign>,
+	valign: Option<VerticalAlign>,
+	/// Whether image alt texts are drawn as captions
+	captions: bool,
+	/// Prefixes captions with "Figure N", numbered across the deck
+	figure_numbers: bool,
+	theme: Theme,
+}
+
+impl FrontMatter {
+	pub fn footer_text(&self) -> String {
+		match self.footer_text {
+			Some(ref footer_text) => footer_text.clone(),
+			None => [&self.author, &self.event, &self.date]
+				.iter()
+				.filter_map(|v| v.as_deref())
+				.collect::<Vec<_>>()
+				.join(" \u{00b7} "),
+		}
+	}
+}
+
+/// Fonts used to render slides. Custom fonts from the front matter replace the embedded ones.
+#[derive(Debug, Copy, Clone)]
+pub struct Fonts {
+	header: Font,
+	body: Font,
+	/// When unset, Cascadia Code variant is picked based on `CodeBlockParams::font_style`
+	code: Option<Font>,
+	/// Glyphs of the fonts above, the embedded ones and the fallback chain
+	glyphs: &'static GlyphCoverage,
+}
+
+impl Default for Fonts {
+	fn default() -> Self {
+		Fonts {
+			header: views::fonts::LATO_BOLD,
+			body: views::fonts::LATO_REGULAR,
+			code: None,
+			glyphs: GlyphCoverage::embedded(),
+		}
+	}
+}
+
+/// Parsed faces of the fonts text may be drawn with, telling which characters they have glyphs
+/// for and how wide those are. Built once when the fonts are loaded and leaked just like the
+/// font data itself, see `commands::load_font`.
+pub struct GlyphCoverage {
+	faces: Vec<FontFace>,
+	/// Indices of `faces` tried in order for characters missing from the font text is drawn with
+	fallback: Vec<usize>,
+}
+
+pub struct FontFace {
+	font: Font,
+	/// `None` for the default font, which is assumed to have every glyph
+	face: Option<ttf_parser::Face<'static>>,
+}
+
+impl FontFace {
+	fn new(font: Font) -> Self {
+		let face = match font {
+			Font::External { name, bytes } => match ttf_parser::Face::from_slice(bytes, 0) {
+				Ok(face) => Some(face),
+				Err(e) => {
+					error!("Failed to parse font {}: {}", name, e);
+					None
+				}
+			},
+			Font::Default => None,
+		};
+		FontFace { font, face }
+	}
+
+	pub fn font(&self) -> Font {
+		self.font
+	}
+
+	fn has_glyph(&self, c: char) -> bool {
+		match self.face {
+			Some(ref face) => face.glyph_index(c).is_some(),
+			None => true,
+		}
+	}
+
+	/// Width of `c` drawn at font `size`, in pixels
+	pub fn advance(&self, c: char, size: f32) -> f32 {
+		let face = match self.face {
+			Some(ref v) => v,
+			None => return size / 2.0,
+		};
+		let units = face
+			.glyph_index(c)
+			.and_then(|glyph| face.glyph_hor_advance(glyph))
+			.unwrap_or(0);
+		f32::from(units) * size / f32::from(face.units_per_em().max(1))
+	}
+}
+
+impl GlyphCoverage {
+	/// Coverage of `fonts`, with `fallback` tried in order for characters missing from them
+	pub fn new(fonts: &[Font], fallback: &[Font]) -> Self {
+		let faces = fonts.iter().chain(fallback).copied().map(FontFace::new);
+		GlyphCoverage {
+			faces: faces.collect(),
+			fallback: (fonts.len()..fonts.len() + fallback.len()).collect(),
+		}
+	}
+
+	/// Fonts embedded in the app, with the bundled fallback font
+	pub fn embedded() -> &'static Self {
+		static EMBEDDED: std::sync::OnceLock<GlyphCoverage> = std::sync::OnceLock::new();
+		EMBEDDED.get_or_init(|| {
+			GlyphCoverage::new(views::fonts::EMBEDDED, &[views::fonts::DEJAVU_SANS])
+		})
+	}
+
+	/// Fonts the coverage was built for, fallback fonts included
+	pub fn fonts(&self) -> impl Iterator<Item = Font> + '_ {
+		self.faces.iter().map(FontFace::font)
+	}
+
+	fn face_idx(&self, font: Font) -> Option<usize> {
+		self.faces
+			.iter()
+			.position(|face| font_name(face.font) == font_name(font))
+	}
+
+	/// Splits `txt` into runs of characters sharing the first font that has glyphs for them,
+	/// `primary` or one of the fallback chain. Characters no font covers stay with `primary`,
+	/// which has to be one of the fonts the coverage was built for.
+	pub fn font_runs<'t>(&self, txt: &'t str, primary: Font) -> Vec<(&FontFace, &'t str)> {
+		let primary_idx = match self.face_idx(primary) {
+			Some(v) => v,
+			None => {
+				warn!("No glyph coverage of {:?}", font_name(primary));
+				return vec![(&self.faces[0], txt)];
+			}
+		};
+		let face_idx_for = |c: char| {
+			if c.is_whitespace() {
+				return primary_idx;
+			}
+			std::iter::once(primary_idx)
+				.chain(self.fallback.iter().copied())
+				.find(|idx| self.faces[*idx].has_glyph(c))
+				.unwrap_or(primary_idx)
+		};
+
+		let mut runs = Vec::new();
+		let mut run_start = 0;
+		let mut run_face_idx = None;
+		for (idx, c) in txt.char_indices() {
+			let face_idx = face_idx_for(c);
+			if let Some(run_face_idx) = run_face_idx {
+				if run_face_idx != face_idx {
+					runs.push((&self.faces[run_face_idx], &txt[run_start..idx]));
+					run_start = idx;
+				}
+			}
+			run_face_idx = Some(face_idx);
+		}
+		if let Some(run_face_idx) = run_face_idx {
+			runs.push((&self.faces[run_face_idx], &txt[run_start..]));
+		}
+		runs
+	}
+}
+
+impl Debug for GlyphCoverage {
+	fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+		f.debug_list().entries(self.fonts().map(font_name)).finish()
+	}
+}
+
+/// Identifies the font, `None` for the default one
+pub fn font_name(font: Font) -> Option<&'static str> {
+	match font {
+		Font::External { name, .. } => Some(name),
+		Font::Default => None,
+	}
 }
 
 #[derive(Debug, Default, Clone)]
 pub struct PresentationState {
 	slide_idx: usize,
+	/// When each fragment of the current slide revealed so far was revealed, on the
+	/// `animation_elapsed` clock. Animated images of a fragment start playing then.
+	fragments_revealed_at: Vec<Duration>,
+	transition: Option<TransitionState>,
+	/// Present while the slide overview grid is shown
+	overview: Option<Overview>,
+	/// Time animated images of the current slide have been playing for
+	animation_elapsed: Duration,
+	last_animation_tick: Option<Instant>,
+	/// Slide number being typed, Enter jumps to it
+	typed_slide_number: Option<usize>,
+	/// Slide shown before the last jump, to go back to it
+	jumped_from: Option<usize>,
+	/// Screen shown instead of the slide
+	blank: Option<Blank>,
+	/// Slide the presentation was left at last time, Enter jumps to it
+	resume_offer: Option<usize>,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Blank {
+	Black,
+	White,
+}
+
+pub const OVERVIEW_COLUMNS: usize = 4;
+
+#[derive(Debug, Default, Clone)]
+pub struct Overview {
+	selected: usize,
+	buttons: Vec<button::State>,
+	scroll: scrollable::State,
+	/// Set when the selection moved with the keyboard, the view scrolls to it
+	scroll_to_selected: bool,
+}
+
+impl Overview {
+	/// Opens the overview at the slide at `idx`
+	pub fn new(idx: usize) -> Self {
+		Overview {
+			selected: idx,
+			scroll_to_selected: true,
+			..Default::default()
+		}
+	}
+
+	/// Moves the selection by `delta` slides, stopping at the first and last of `slide_count`
+	pub fn move_selection(&mut self, delta: isize, slide_count: usize) {
+		self.selected = moved_selection(self.selected, delta, slide_count);
+		self.scroll_to_selected = true;
+	}
+}
+
+/// Time animated images of the fragment at `fragment_idx` have been playing for, `elapsed` after
+/// the slide was entered. Fragments are counted from 1, 0 is the part of the slide outside of them.
+pub fn fragment_elapsed(
+	elapsed: Duration,
+	revealed_at: &[Duration],
+	fragment_idx: usize,
+) -> Duration {
+	match fragment_idx.checked_sub(1) {
+		Some(idx) => revealed_at
+			.get(idx)
+			.map_or(Duration::ZERO, |revealed| elapsed.saturating_sub(*revealed)),
+		None => elapsed,
+	}
+}
+
+/// Index `delta` entries away from `selected`, within `count` entries
+fn moved_selection(selected: usize, delta: isize, count: usize) -> usize {
+	let last_idx = count.saturating_sub(1) as isize;
+	(selected as isize).saturating_add(delta).clamp(0, last_idx) as usize
+}
+
+/// Selection on the welcome screen, which lists the recent presentations and the example after
+/// them
+#[derive(Debug, Default)]
+pub struct Welcome {
+	selected: usize,
+	buttons: Vec<button::State>,
+	/// Of the recent presentations, the example can't be removed
+	remove_buttons: Vec<button::State>,
+	scroll: scrollable::State,
+	/// Set when the selection moved with the keyboard, the view scrolls to it
+	scroll_to_selected: bool,
+}
+
+impl Welcome {
+	/// Moves the selection by `delta` entries, stopping at the first and last of `entry_count`
+	pub fn move_selection(&mut self, delta: isize, entry_count: usize) {
+		self.selected = moved_selection(self.selected, delta, entry_count);
+		self.scroll_to_selected = true;
+	}
+}
+
+impl PresentationState {
+	/// Reveals the next fragment of the current slide or moves on to the next slide
+	pub fn next(&mut self, presentation: &Presentation) {
+		let fragment_count = presentation
+			.slides
+			.get(self.slide_idx)
+			.map(Slide::fragment_count)
+			.unwrap_or(0);
+		if self.fragments_revealed_at.len() < fragment_count {
+			// The clock only ticks while something is animated, so it's behind after a pause
+			self.advance_animations(Instant::now());
+			self.fragments_revealed_at.push(self.animation_elapsed);
+			return;
+		}
+		let idx = (self.slide_idx + 1).min(presentation.slides.len().saturating_sub(1));
+		self.go_to(idx, presentation.transition_for(idx));
+	}
+
+	/// Hides the last revealed fragment or moves back to the previous slide, with all of its
+	/// fragments revealed
+	pub fn previous(&mut self, presentation: &Presentation) {
+		if self.fragments_revealed_at.pop().is_some() {
+			return;
+		}
+		let idx = self.slide_idx.saturating_sub(1);
+		if idx == self.slide_idx {
+			return;
+		}
+		self.go_to(idx, presentation.transition_for(idx));
+		let fragment_count = presentation
+			.slides
+			.get(idx)
+			.map_or(0, Slide::fragment_count);
+		self.fragments_revealed_at = vec![Duration::ZERO; fragment_count];
+	}
+
+	/// Goes to the slide at `idx`, remembering the current one for `jump_back`
+	pub fn jump_to(&mut self, idx: usize, presentation: &Presentation) {
+		let idx = idx.min(presentation.slides.len().saturating_sub(1));
+		if idx == self.slide_idx {
+			return;
+		}
+		self.jumped_from = Some(self.slide_idx);
+		self.go_to(idx, presentation.transition_for(idx));
+	}
+
+	/// Returns to the slide shown before the last jump. Repeating it goes back and forth.
+	pub fn jump_back(&mut self, presentation: &Presentation) {
+		if let Some(idx) = self.jumped_from {
+			self.jump_to(idx, presentation);
+		}
+	}
+
+	/// Keeps showing the same slide after the deck was edited and reloaded
+	pub fn reanchor(&mut self, old: &Presentation, new: &Presentation) {
+		let slide_idx = new.find_slide(old, self.slide_idx);
+		if slide_idx != self.slide_idx {
+			info!(
+				"Slide {} is now slide {}",
+				self.slide_idx + 1,
+				slide_idx + 1
+			);
+		}
+		self.slide_idx = slide_idx;
+		let fragment_count = new
+			.slides
+			.get(slide_idx)
+			.map(Slide::fragment_count)
+			.unwrap_or(0);
+		self.fragments_revealed_at.truncate(fragment_count);
+		self.transition = None;
+		self.jumped_from = self.jumped_from.map(|idx| new.find_slide(old, idx));
+		if let Some(ref mut overview) = self.overview {
+			overview.selected = overview.selected.min(new.slides.len().saturating_sub(1));
+		}
+	}
+
+	pub fn toggle_blank(&mut self, blank: Blank) {
+		if self.blank == Some(blank) {
+			self.blank = None;
+		} else {
+			self.blank = Some(blank);
+		}
+	}
+
+	pub fn type_digit(&mut self, digit: usize) {
+		let number = self.typed_slide_number.unwrap_or(0);
+		self.typed_slide_number = Some(number.saturating_mul(10).saturating_add(digit));
+	}
+
+	/// Jumps to the typed slide number, counted from 1
+	pub fn jump_to_typed(&mut self, presentation: &Presentation) {
+		if let Some(number) = self.typed_slide_number.take() {
+			self.jump_to(number.saturating_sub(1), presentation);
+		}
+	}
+
+	/// Switches to the slide at `idx`.
+	///
+	/// Navigating while a transition is still running cancels it and shows the target slide
+	/// right away, so that quickly skipping through the deck doesn't queue up animations.
+	pub fn go_to(&mut self, idx: usize, transition: Transition) {
+		if idx == self.slide_idx {
+			return;
+		}
+		self.fragments_revealed_at.clear();
+		self.animation_elapsed = Duration::ZERO;
+		self.last_animation_tick = None;
+		let cancelled = self.transition.take().is_some();
+		let from_idx = std::mem::replace(&mut self.slide_idx, idx);
+		if cancelled || transition.kind == TransitionKind::None || transition.duration.is_zero() {
+			return;
+		}
+		self.transition = Some(TransitionState {
+			from_idx,
+			transition,
+			started: Instant::now(),
+			progress: 0.0,
+		});
+	}
+
+	fn advance_transition(&mut self, now: Instant) {
+		if let Some(ref mut transition_state) = self.transition {
+			let elapsed = now.saturating_duration_since(transition_state.started);
+			let progress =
+				elapsed.as_secs_f32() / transition_state.transition.duration.as_secs_f32();
+			if progress >= 1.0 {
+				self.transition = None;
+			} else {
+				transition_state.progress = progress;
+			}
+		}
+	}
+
+	fn advance_animations(&mut self, now: Instant) {
+		if let Some(last_tick) = self.last_animation_tick {
+			self.animation_elapsed += now.saturating_duration_since(last_tick);
+		}
+		self.last_animation_tick = Some(now);
+	}
+
+	/// Whether the current slide has animated images that are still playing
+	fn is_animating(&self, presentation: &Presentation) -> bool {
+		presentation
+			.slides
+			.get(self.slide_idx)
+			.map(|slide| slide.is_animating(self.animation_elapsed, &self.fragments_revealed_at))
+			.unwrap_or(false)
+	}
+}
+
+#[derive(Debug, Clone)]
+pub struct TransitionState {
+	from_idx: usize,
+	transition: Transition,
+	started: Instant,
+	/// In range `0.0..1.0`
+	progress: f32,
+}
+
+pub const DEFAULT_TRANSITION_DURATION: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Transition {
+	kind: TransitionKind,
+	duration: Duration,
+}
+
+/// Colors of the slides
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Theme {
+	/// White text on black
+	Dark,
+	/// Dark text on white
+	Light,
+}
+
+impl Default for Theme {
+	fn default() -> Self {
+		Theme::Dark
+	}
+}
+
+impl FromStr for Theme {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Ok(match s {
+			"dark" => Theme::Dark,
+			"light" => Theme::Light,
+			_ => anyhow::bail!("Unknown theme {}", s),
+		})
+	}
+}
+
+/// Images can't be drawn translucent yet, so `fade` and `zoom` show them at full opacity
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TransitionKind {
+	/// `none`
+	None,
+	/// `fade`, fades the previous slide out and the next one in
+	Fade,
+	/// `slide-left`, or `slide_left`, moves the next slide in from the right
+	SlideLeft,
+	/// `zoom`, grows and fades the next slide in
+	Zoom,
+}
+
+impl FromStr for TransitionKind {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Ok(match s {
+			"none" => TransitionKind::None,
+			"fade" => TransitionKind::Fade,
+			"slide-left" | "slide_left" => TransitionKind::SlideLeft,
+			"zoom" => TransitionKind::Zoom,
+			_ => anyhow::bail!("Unknown transition {}", s),
+		})
+	}
 }
 
 #[derive(Debug, Default, Eq, PartialEq)]
 pub struct Slide {
-	nodes: Vec<SlideNode>,
+	nodes: Vec<Node>,
+	/// Parsed, but not loaded or drawn: iced can't stack the slide on top of an image yet.
+	/// Tracking issue: https://github.com/hecrj/iced/issues/450
 	background: Option<Image>,
+	params: SlideParams,
+}
+
+impl Slide {
+	pub fn fragment_count(&self) -> usize {
+		self.nodes
+			.iter()
+			.filter(|node| node.params.fragment)
+			.count()
+	}
+
+	/// Explicit id of the slide, or of its first node that has one
+	pub fn id(&self) -> Option<&str> {
+		self.params
+			.id
+			.as_deref()
+			.or_else(|| self.nodes.iter().find_map(|node| node.params.id.as_deref()))
+	}
+
+	pub fn first_header(&self) -> Option<&str> {
+		self.nodes.iter().find_map(|node| match node.kind {
+			SlideNode::Header(_, ref txt) => Some(txt.as_str()),
+			_ => None,
+		})
+	}
+
+	fn words(&self) -> HashSet<&str> {
+		let mut words = HashSet::new();
+		for node in &self.nodes {
+			match node.kind {
+				SlideNode::Header(_, ref txt)
+				| SlideNode::Text(ref txt)
+				| SlideNode::CodeBlock(_, _, ref txt) => words.extend(txt.split_whitespace()),
+				SlideNode::UnnumberedList(ref items) | SlideNode::NumberedList(ref items) => {
+					words.extend(items.iter().flat_map(|item| item.split_whitespace()))
+				}
+				SlideNode::Image(ref image) => {
+					words.insert(image.path.as_str());
+				}
+				SlideNode::Comment(_) => (),
+			}
+		}
+		words
+	}
+
+	/// Share of words the slides have in common, from 0 to 1 for slides with the same words
+	pub fn similarity(&self, other: &Slide) -> f32 {
+		let (words, other_words) = (self.words(), other.words());
+		let all_words = words.union(&other_words).count();
+		if all_words == 0 {
+			return 1.0;
+		}
+		words.intersection(&other_words).count() as f32 / all_words as f32
+	}
+
+	/// Whether animated images of the slide and its revealed fragments are still playing, given
+	/// the times the fragments were `revealed_at`
+	pub fn is_animating(&self, elapsed: Duration, revealed_at: &[Duration]) -> bool {
+		let mut fragment_idx = 0;
+		self.nodes.iter().any(|node| {
+			let node_fragment = if node.params.fragment {
+				fragment_idx += 1;
+				if fragment_idx > revealed_at.len() {
+					return false;
+				}
+				fragment_idx
+			} else {
+				0
+			};
+			match node.kind {
+				SlideNode::Image(ref image) => {
+					image.is_animating(fragment_elapsed(elapsed, revealed_at, node_fragment))
+				}
+				_ => false,
+			}
+		})
+	}
+}
+
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct SlideParams {
+	transition: Option<TransitionKind>,
+	transition_duration: Option<Duration>,
+	/// Overrides front matter footer visibility, e.g. to hide it on title slides
+	footer: Option<bool>,
+	/// Default alignment of the slide nodes
+	align: Option<HorizontalAlign>,
+	/// Vertical placement of the slide content
+	valign: Option<VerticalAlign>,
+	/// Identifies the slide when the deck is reloaded, so edits elsewhere don't move the view
+	id: Option<String>,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct Node {
+	kind: SlideNode,
+	params: NodeParams,
+}
+
+impl From<SlideNode> for Node {
+	fn from(kind: SlideNode) -> Self {
+		Node {
+			kind,
+			params: NodeParams::default(),
+		}
+	}
+}
+
+/// Params applicable to every kind of node
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct NodeParams {
+	/// Overrides slide alignment
+	align: Option<HorizontalAlign>,
+	/// Font size
+	size: Option<u16>,
+	color: Option<Rgb>,
+	/// Fragments are hidden at first and revealed one by one when advancing the slide
+	fragment: bool,
+	/// Lets decks tag nodes for tooling. Also identifies the slide when the deck is reloaded.
+	id: Option<String>,
+	/// Not used by the renderer, lets decks tag nodes for tooling
+	#[allow(dead_code)]
+	class: Option<String>,
+}
+
+/// Color given as `#rrggbb`, `#rgb` or one of a few basic color names
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Rgb(u8, u8, u8);
+
+impl FromStr for Rgb {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let hex = match s {
+			"white" => "#ffffff",
+			"black" => "#000000",
+			"gray" | "grey" => "#808080",
+			"red" => "#ff0000",
+			"green" => "#00ff00",
+			"blue" => "#0000ff",
+			"yellow" => "#ffff00",
+			s => s,
+		};
+		let digits = match hex.strip_prefix('#') {
+			Some(v) if v.chars().all(|c| c.is_ascii_hexdigit()) => v,
+			_ => anyhow::bail!("Invalid color {}", s),
+		};
+		let digits: String = match digits.len() {
+			3 => digits.chars().flat_map(|c| [c, c]).collect(),
+			6 => digits.to_string(),
+			_ => anyhow::bail!("Invalid color {}", s),
+		};
+		let channel = |idx: usize| u8::from_str_radix(&digits[idx..idx + 2], 16);
+		Ok(Rgb(channel(0)?, channel(2)?, channel(4)?))
+	}
+}
+
+impl From<Rgb> for Color {
+	fn from(Rgb(r, g, b): Rgb) -> Self {
+		Color::from_rgb8(r, g, b)
+	}
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum HorizontalAlign {
+	Left,
+	Center,
+	Right,
+}
+
+impl FromStr for HorizontalAlign {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		use HorizontalAlign::*;
+		Ok(match s {
+			"left" => Left,
+			"center" => Center,
+			"right" => Right,
+			_ => anyhow::bail!("Unknown alignment {}", s),
+		})
+	}
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum VerticalAlign {
+	Top,
+	Center,
+	Bottom,
+}
+
+impl FromStr for VerticalAlign {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		use VerticalAlign::*;
+		Ok(match s {
+			"top" => Top,
+			"center" => Center,
+			"bottom" => Bottom,
+			_ => anyhow::bail!("Unknown vertical alignment {}", s),
+		})
+	}
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -86,18 +1053,274 @@ pub enum CodeFontStyle {
 	ExtraLight,
 }
 
-#[derive(Debug)]
-pub struct Image {
-	path: String,
-	alt_text: String,
-	params: ImageParams,
-	handle: Option<image::Handle>,
-}
+impl FromStr for CodeFontStyle {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		use CodeFontStyle::*;
+		Ok(match s {
+			"regular" => Regular,
+			"bold" => Bold,
+			"semi_bold" => SemiBold,
+			"light" => Light,
+			"semi_light" => SemiLight,
+			"extra_light" => ExtraLight,
+			_ => anyhow::bail!("Unknown font style {}", s),
+		})
+	}
+}
+
+/// Where an image is in the presentation, by slide and node index
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct ImageLocation {
+	slide_idx: usize,
+	node_idx: usize,
+}
+
+#[derive(Debug)]
+pub struct Image {
+	path: String,
+	alt_text: String,
+	params: ImageParams,
+	/// First frame of animated images
+	handle: Option<image::Handle>,
+	animation: Option<Animation>,
+	/// Set instead of the handle for SVG files, which are rasterized at the size they're drawn at
+	vector: Option<VectorImage>,
+	/// Size of the (cropped) source image. Layout is based on it, as the decoded pixels may be
+	/// downscaled to what fits on the canvas. The file at `path` keeps the full resolution.
+	source_size: Option<Size>,
+	/// Why the image couldn't be loaded, a placeholder is drawn instead
+	error: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ImageParams {
+	/// Scale as a fraction of the image size, `50%` is stored as `0.5`. Ignored when width or
+	/// height is given.
+	scale: Option<f32>,
+	width: Option<ImageSize>,
+	height: Option<ImageSize>,
+	/// Bounds the image is shrunk to fit in, keeping its aspect ratio
+	max_width: Option<ImageSize>,
+	max_height: Option<ImageSize>,
+	/// Places the image beside the following node
+	float: Option<ImageFloat>,
+	/// Part of the image to show, in pixels of the source image
+	crop: Option<Crop>,
+	/// In pixels of the source image
+	corner_radius: Option<f32>,
+	/// Whether the alt text is drawn as a caption, overrides the front matter
+	caption: Option<bool>,
+	/// Whether animated images loop or stop at the last frame
+	playback: Playback,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ImageSize {
+	Pixels(f32),
+	/// Percentage of the slide size
+	Percent(f32),
+}
+
+impl ImageParams {
+	/// Size the image is drawn at, before the transition scale. An explicit width or height wins
+	/// over the scale, a missing one follows the aspect ratio. Max bounds shrink the result.
+	/// Percent sizes are relative to the `slide_area` the slide nodes are laid out in.
+	pub fn display_size(&self, natural_size: Size, slide_area: Size) -> Size {
+		let aspect_ratio = natural_size.width / natural_size.height.max(1.0);
+		let width = self.width.map(|v| v.resolve(slide_area.width));
+		let height = self.height.map(|v| v.resolve(slide_area.height));
+		let size = match (width, height) {
+			(Some(width), Some(height)) => Size::new(width, height),
+			(Some(width), None) => Size::new(width, width / aspect_ratio),
+			(None, Some(height)) => Size::new(height * aspect_ratio, height),
+			(None, None) => {
+				let scale = self.scale.unwrap_or(1.0);
+				Size::new(natural_size.width * scale, natural_size.height * scale)
+			}
+		};
+
+		let mut fit = 1.0_f32;
+		if let Some(max_width) = self.max_width {
+			fit = fit.min(max_width.resolve(slide_area.width) / size.width.max(1.0));
+		}
+		if let Some(max_height) = self.max_height {
+			fit = fit.min(max_height.resolve(slide_area.height) / size.height.max(1.0));
+		}
+		Size::new(size.width * fit, size.height * fit)
+	}
+}
+
+impl ImageSize {
+	pub fn resolve(self, slide_size: f32) -> f32 {
+		match self {
+			ImageSize::Pixels(px) => px,
+			ImageSize::Percent(percent) => slide_size * percent / 100.0,
+		}
+	}
+}
+
+impl FromStr for ImageSize {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (value, size): (&str, fn(f32) -> ImageSize) = match s.strip_suffix('%') {
+			Some(percent) => (percent, ImageSize::Percent),
+			None => (s.strip_suffix("px").unwrap_or(s), ImageSize::Pixels),
+		};
+		let value = value.trim().parse::<f32>()?;
+		if !value.is_finite() || value < 0.0 {
+			anyhow::bail!("Invalid size {}", s);
+		}
+		Ok(size(value))
+	}
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ImageFloat {
+	Left,
+	Right,
+}
+
+impl FromStr for ImageFloat {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Ok(match s {
+			"left" => ImageFloat::Left,
+			"right" => ImageFloat::Right,
+			_ => anyhow::bail!("Unknown float {}", s),
+		})
+	}
+}
+
+/// Given as `x y width height`
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Crop {
+	x: u32,
+	y: u32,
+	width: u32,
+	height: u32,
+}
+
+impl FromStr for Crop {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let values = s
+			.split_whitespace()
+			.map(|v| v.strip_suffix("px").unwrap_or(v).parse::<u32>())
+			.collect::<Result<Vec<_>, _>>()?;
+		match values[..] {
+			[x, y, width, height] if width > 0 && height > 0 => Ok(Crop {
+				x,
+				y,
+				width,
+				height,
+			}),
+			_ => anyhow::bail!("Invalid crop {}, expected `x y width height`", s),
+		}
+	}
+}
+
+impl Image {
+	/// Drops what was loaded from the file, before loading it again
+	pub fn clear_decoded(&mut self) {
+		self.handle = None;
+		self.animation = None;
+		self.vector = None;
+		self.source_size = None;
+		self.error = None;
+	}
+
+	pub fn is_loaded(&self) -> bool {
+		self.handle.is_some() || self.vector.is_some()
+	}
+
+	/// Frame to draw `elapsed` after the slide was entered. Still images have a single frame.
+	pub fn handle_at(&self, elapsed: Duration) -> Option<&image::Handle> {
+		match self.animation {
+			Some(ref animation) => Some(animation.frame_at(elapsed, self.params.playback)),
+			None => self.handle.as_ref(),
+		}
+	}
+
+	fn is_animating(&self, elapsed: Duration) -> bool {
+		match self.animation {
+			Some(ref animation) => {
+				self.params.playback == Playback::Loop || elapsed < animation.duration
+			}
+			None => false,
+		}
+	}
+}
+
+#[derive(Debug, Clone)]
+pub struct VectorImage {
+	handle: svg::Handle,
+	/// Size from the SVG file, in pixels
+	size: Size,
+}
+
+/// Frames of an animated GIF or APNG, with how long each of them is shown
+#[derive(Debug, Clone)]
+pub struct Animation {
+	frames: Vec<(image::Handle, Duration)>,
+	/// Sum of the frame delays
+	duration: Duration,
+}
+
+impl Animation {
+	/// `frames` can't be empty
+	pub fn new(frames: Vec<(image::Handle, Duration)>) -> Self {
+		assert!(!frames.is_empty(), "animation without frames");
+		let duration = frames.iter().map(|(_, delay)| *delay).sum();
+		Animation { frames, duration }
+	}
+
+	fn frame_at(&self, elapsed: Duration, playback: Playback) -> &image::Handle {
+		let mut time = match playback {
+			_ if self.duration.is_zero() => Duration::ZERO,
+			Playback::Loop => {
+				Duration::from_nanos((elapsed.as_nanos() % self.duration.as_nanos()) as u64)
+			}
+			Playback::Once => elapsed,
+		};
+		for (handle, delay) in &self.frames {
+			if time < *delay {
+				return handle;
+			}
+			time -= *delay;
+		}
+		// Played once, the last frame stays
+		&self.frames[self.frames.len() - 1].0
+	}
+}
+
+/// How animated images are played
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Playback {
+	Loop,
+	Once,
+}
+
+impl Default for Playback {
+	fn default() -> Self {
+		Playback::Loop
+	}
+}
+
+impl FromStr for Playback {
+	type Err = anyhow::Error;
 
-#[derive(Debug, Default)]
-pub struct ImageParams {
-	/// Scale in percentage
-	scale: Option<f32>,
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Ok(match s {
+			"loop" => Playback::Loop,
+			"once" => Playback::Once,
+			_ => anyhow::bail!("Unknown playback {}, expected `loop` or `once`", s),
+		})
+	}
 }
 
 impl PartialEq for Image {
@@ -151,20 +1374,41 @@ impl HeaderSize {
 impl Application for App {
 	type Executor = iced::executor::Default;
 	type Message = Message;
-	type Flags = ();
+	type Flags = cli::PresentOptions;
 
-	fn new(_flags: Self::Flags) -> (Self, Command<Self::Message>) {
+	fn new(options: Self::Flags) -> (Self, Command<Self::Message>) {
+		let (width, height) = window::Settings::default().size;
+		let viewport = Size::new(width as f32, height as f32);
 		let app = App {
-			stage: Stage::WelcomeScreen,
-			mode: Mode::Windowed,
+			stage: Stage::WelcomeScreen(Default::default()),
+			mode: if options.fullscreen {
+				Mode::Fullscreen
+			} else {
+				Mode::Windowed
+			},
+			path: options.path.clone(),
+			viewport,
+			image_cache: Default::default(),
+			canvas: canvas_for(viewport),
+			key_bindings: config::KeyBindings::load(),
+			settings: config::Settings::load(),
+			wheel: Default::default(),
+			recent: recent::RecentPresentations::load(),
+			last_slides: recent::LastSlides::load(),
+			options,
+			saved_slide: None,
+			exiting: false,
+		};
+		let command = match app.path {
+			Some(_) => app.load(),
+			None => Command::none(),
 		};
-		let command = Command::perform(commands::load_from_args(), Message::Loaded);
 		(app, command)
 	}
 
 	fn title(&self) -> String {
 		match self.stage {
-			Stage::WelcomeScreen => String::from("Presentation"),
+			Stage::WelcomeScreen(_) => String::from("Presentation"),
 			Stage::Presentation {
 				ref presentation, ..
 			} => presentation.title.clone(),
@@ -177,33 +1421,55 @@ impl Application for App {
 		_clipboard: &mut Clipboard,
 	) -> Command<Self::Message> {
 		match message {
-			Message::Loaded(Ok(presentation)) => {
+			Message::Loaded(Ok(mut presentation)) => {
+				if let Some(theme) = self.options.theme {
+					presentation.front_matter.theme = theme;
+				}
 				info!("Loaded presentation \"{}\"", presentation.title);
 				info!("Slides count: {}", presentation.slides.len());
 
+				// Opening a different file starts over, with a new file watcher
 				let (state, file_watcher) = match self.stage {
 					Stage::Presentation {
+						presentation: ref old_presentation,
 						ref state,
 						ref mut file_watcher,
-						..
-					} => (state.clone(), file_watcher.take()),
-					_ => (PresentationState::default(), None),
+					} if old_presentation.source == presentation.source => {
+						let mut state = state.clone();
+						state.reanchor(old_presentation, &presentation);
+						// Images and fonts may have been added or removed
+						if let Some(ref file_watcher) = file_watcher {
+							if let Err(e) = file_watcher.set_files(presentation.watched_files()) {
+								error!("Failed to update watched files: {:?}", e);
+							}
+						}
+						(state, file_watcher.take())
+					}
+					_ => {
+						self.saved_slide = None;
+						let start_slide = self.options.start_slide.take();
+						(self.resume_state(&presentation, start_slide), None)
+					}
 				};
 
-				let cmd = if file_watcher.is_none() {
+				let cmd = if file_watcher.is_none() && !self.options.no_watch {
 					Command::perform(
-						commands::start_file_watcher(presentation.path.clone()),
+						commands::start_file_watcher(presentation.watched_files()),
 						Message::FileWatcherStarted,
 					)
 				} else {
 					Command::none()
 				};
 
+				self.path = presentation.source.clone();
+				self.remember(&presentation);
 				self.stage = Stage::Presentation {
 					presentation,
 					state,
 					file_watcher,
 				};
+				// Reloading can move the slide, resuming starts at another one
+				self.save_last_slide();
 
 				return cmd;
 			}
@@ -220,11 +1486,116 @@ impl Application for App {
 				}
 			}
 			Message::FileWatcherStarted(None) => (),
-			Message::Reloaded => {
+			Message::Reloaded(changed) => {
+				if let Stage::Presentation {
+					ref presentation, ..
+				} = self.stage
+				{
+					let images = changed.and_then(|changed| presentation.images_in(&changed));
+					if let Some(images) = images {
+						info!("Images have been updated. Reloading them");
+						return Command::perform(
+							commands::reload_images(images, self.image_cache.clone(), self.canvas),
+							Message::ImagesReloaded,
+						);
+					}
+				}
 				info!("Presentation file has been updated. Reloading");
-				return Command::perform(commands::load_from_args(), Message::Loaded);
+				return self.load();
+			}
+			Message::ImagesReloaded(images) => {
+				if let Stage::Presentation {
+					ref mut presentation,
+					..
+				} = self.stage
+				{
+					let presentation_dir = presentation.path.clone();
+					for (location, path, params, decoded) in images {
+						// Reloading the presentation meanwhile can move or change the images
+						let image = presentation.image_mut(location).filter(|image| {
+							presentation_dir.join(&image.path) == path && image.params == params
+						});
+						if let Some(image) = image {
+							image.clear_decoded();
+							match decoded {
+								Ok(decoded) => decoded.apply_to(image),
+								Err(e) => image.error = Some(e.to_string()),
+							}
+						}
+					}
+				}
+			}
+			Message::WelcomeSelected(idx) => return self.open_welcome_entry(idx),
+			Message::WelcomeRemoved(idx) => self.remove_welcome_entry(idx),
+			Message::FilePicked(Some(path)) => return self.open(path),
+			Message::FilePicked(None) => (),
+			Message::FileDropped(path) => {
+				if commands::is_markdown(&path) {
+					return self.open(path);
+				}
+				warn!(
+					"Ignoring dropped {}, it isn't a markdown file",
+					path.display()
+				);
+			}
+			Message::KeyboardEvent(e) => {
+				let command = self.handle_keyboard_event(e);
+				self.save_last_slide();
+				return command;
+			}
+			Message::MouseEvent(e) => {
+				let command = self.handle_mouse_event(e);
+				self.save_last_slide();
+				return command;
+			}
+			Message::OverviewSelected(idx) => {
+				if let Stage::Presentation {
+					ref presentation,
+					ref mut state,
+					..
+				} = self.stage
+				{
+					state.overview = None;
+					state.jump_to(idx, presentation);
+				}
+				self.save_last_slide();
+			}
+			Message::Tick(now) => {
+				if let Stage::Presentation { ref mut state, .. } = self.stage {
+					state.advance_transition(now);
+					state.advance_animations(now);
+				}
+			}
+			Message::CloseRequested => {
+				self.save_last_slide();
+				// The writes are queued to a background thread, which exits along with the app
+				recent::flush_data_files();
+				self.exiting = true;
+			}
+			Message::WindowResized(viewport) => {
+				self.viewport = viewport;
+				let canvas = canvas_for(viewport);
+				if canvas.width > self.canvas.width || canvas.height > self.canvas.height {
+					self.canvas = Size::new(
+						canvas.width.max(self.canvas.width),
+						canvas.height.max(self.canvas.height),
+					);
+					if let Stage::Presentation {
+						ref presentation, ..
+					} = self.stage
+					{
+						info!("Window has grown. Decoding images at a higher resolution");
+						return Command::perform(
+							commands::reload_images(
+								presentation.raster_images(),
+								self.image_cache.clone(),
+								self.canvas,
+							),
+							Message::ImagesReloaded,
+						);
+					}
+				}
 			}
-			Message::KeyboardEvent(e) => return self.handle_keyboard_event(e),
 		}
 
 		Command::none()
@@ -232,12 +1603,31 @@ impl Application for App {
 
 	fn view(&mut self) -> Element<Self::Message> {
 		match self.stage {
-			Stage::WelcomeScreen => views::welcome_screen(),
+			Stage::WelcomeScreen(ref mut welcome) => {
+				views::welcome_screen(&self.recent, welcome, &self.key_bindings, self.viewport)
+			}
 			Stage::Presentation {
 				ref presentation,
-				ref state,
+				ref mut state,
 				..
-			} => views::presentation(presentation, state),
+			} => match (&mut state.overview, state.blank) {
+				(Some(overview), _) => views::overview(presentation, overview, self.viewport),
+				(None, Some(blank)) => views::blank(blank),
+				(None, None) => match self.options.aspect {
+					Some(aspect) => {
+						let area = letterbox(self.viewport, aspect);
+						let theme = presentation.front_matter.theme;
+						views::letterbox(
+							views::presentation(presentation, state, &self.key_bindings, area),
+							area,
+							theme,
+						)
+					}
+					None => {
+						views::presentation(presentation, state, &self.key_bindings, self.viewport)
+					}
+				},
+			},
 		}
 	}
 
@@ -248,13 +1638,34 @@ impl Application for App {
 			..
 		} = &self.stage
 		{
-			let sub =
-				iced::Subscription::from_recipe(file_watcher.recipe()).map(|_| Message::Reloaded);
+			let sub = iced::Subscription::from_recipe(file_watcher.recipe()).map(Message::Reloaded);
 			subscriptions.push(sub);
 		}
+		if let Stage::Presentation {
+			presentation,
+			state,
+			..
+		} = &self.stage
+		{
+			// The slide isn't drawn behind the overview or a blank screen
+			let slide_shown = state.overview.is_none() && state.blank.is_none();
+			if slide_shown && (state.transition.is_some() || state.is_animating(presentation)) {
+				subscriptions.push(iced::time::every(FRAME_DURATION).map(Message::Tick));
+			}
+		}
 		let sub = iced_native::subscription::events_with(|ev, status| match (ev, status) {
 			(_, Status::Captured) => None,
 			(Event::Keyboard(e), Status::Ignored) => Some(Message::KeyboardEvent(e)),
+			(Event::Mouse(e), Status::Ignored) => Some(Message::MouseEvent(e)),
+			(Event::Window(window::Event::Resized { width, height }), Status::Ignored) => Some(
+				Message::WindowResized(Size::new(width as f32, height as f32)),
+			),
+			(Event::Window(window::Event::FileDropped(path)), Status::Ignored) => {
+				Some(Message::FileDropped(path))
+			}
+			(Event::Window(window::Event::CloseRequested), Status::Ignored) => {
+				Some(Message::CloseRequested)
+			}
 			(_, Status::Ignored) => None,
 		});
 		subscriptions.push(sub);
@@ -265,20 +1676,118 @@ impl Application for App {
 		self.mode
 	}
 
+	fn should_exit(&self) -> bool {
+		self.exiting
+	}
+
 	fn background_color(&self) -> Color {
-		Color::from_rgb8(0, 0, 0)
+		match self.stage {
+			// Letterboxed slides draw their own background
+			Stage::Presentation {
+				ref presentation, ..
+			} if self.options.aspect.is_none() => presentation.front_matter.theme.background(),
+			_ => Color::from_rgb8(0, 0, 0),
+		}
 	}
 
 	// fn scale_factor(&self) -> f64 { }
 }
 
+const FRAME_DURATION: Duration = Duration::from_millis(16);
+
+/// Largest part of the viewport with the `aspect` ratio of width to height
+pub fn letterbox(viewport: Size, aspect: f32) -> Size {
+	let width = viewport.width.min(viewport.height * aspect);
+	Size::new(width, width / aspect)
+}
+
+/// Scroll distance that changes the slide, in lines. Trackpads report pixels.
+const WHEEL_STEP_LINES: f32 = 1.0;
+const WHEEL_PIXELS_PER_LINE: f32 = 50.0;
+/// Scrolling is ignored for a while after changing the slide, so that a single swipe on a
+/// trackpad with its momentum doesn't fly through the deck
+const WHEEL_COOLDOWN: Duration = Duration::from_millis(400);
+
+/// Turns scrolling into slide changes
+#[derive(Debug, Default)]
+pub struct WheelNavigation {
+	/// Scrolled lines not turned into a slide change yet, positive is up
+	accumulated: f32,
+	last_step: Option<Instant>,
+}
+
+impl WheelNavigation {
+	pub fn scroll(&mut self, delta: mouse::ScrollDelta, now: Instant) -> Option<config::Action> {
+		if let Some(last_step) = self.last_step {
+			if now.saturating_duration_since(last_step) < WHEEL_COOLDOWN {
+				return None;
+			}
+		}
+		self.accumulated += match delta {
+			mouse::ScrollDelta::Lines { y, .. } => y,
+			mouse::ScrollDelta::Pixels { y, .. } => y / WHEEL_PIXELS_PER_LINE,
+		};
+		if self.accumulated.abs() < WHEEL_STEP_LINES {
+			return None;
+		}
+
+		let action = if self.accumulated < 0.0 {
+			config::Action::Next
+		} else {
+			config::Action::Previous
+		};
+		self.accumulated = 0.0;
+		self.last_step = Some(now);
+		Some(action)
+	}
+}
+
+/// Images are loaded for the window size rounded up to it, so that resizing the window doesn't
+/// reload them on every step
+const CANVAS_STEP: f32 = 512.0;
+
+fn canvas_for(viewport: Size) -> Size {
+	Size::new(
+		(viewport.width / CANVAS_STEP).ceil() * CANVAS_STEP,
+		(viewport.height / CANVAS_STEP).ceil() * CANVAS_STEP,
+	)
+}
+
+/// Editors often save in several steps (write a temp file, rename it, remove the backup), so
+/// changes are reported once they stop for this long
+const FILE_WATCH_DEBOUNCE: Duration = Duration::from_millis(200);
+const FILE_WATCH_REARM_DELAY: Duration = Duration::from_secs(1);
+
+/// Watched files that changed in a burst, as given by [`watched_path`]. `None` when changes may
+/// have been missed and everything has to be reloaded.
+pub type ChangedFiles = Option<HashSet<PathBuf>>;
+
+/// How `file` appears in file watcher events, `None` if its directory doesn't exist
+pub fn watched_path(file: &Path) -> Option<PathBuf> {
+	let name = file.file_name()?;
+	let dir = match file.parent() {
+		Some(dir) if !dir.as_os_str().is_empty() => dir,
+		_ => Path::new("."),
+	};
+	Some(dir.canonicalize().ok()?.join(name))
+}
+
+/// Watches directories of the files rather than the files themselves, so that files replaced by
+/// renaming keep being watched
 pub struct FileWatch {
-	#[allow(dead_code)]
-	watcher: RecommendedWatcher,
-	sender: tokio::sync::broadcast::Sender<()>,
+	watcher: Arc<Mutex<RecommendedWatcher>>,
+	watched: Arc<Mutex<WatchedFiles>>,
+	sender: tokio::sync::broadcast::Sender<ChangedFiles>,
 	id: usize,
 }
 
+#[derive(Default)]
+struct WatchedFiles {
+	/// Files as given by [`watched_path`]
+	files: HashSet<PathBuf>,
+	dirs: HashSet<PathBuf>,
+}
+
 impl Debug for FileWatch {
 	fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
 		f.debug_struct("FileWatch").field("id", &self.id).finish()
@@ -286,49 +1795,166 @@ impl Debug for FileWatch {
 }
 
 impl FileWatch {
-	pub async fn new(path: PathBuf) -> Result<Self> {
-		info!("Setting up file watcher for {}", path.display());
+	pub async fn new(files: Vec<PathBuf>) -> Result<Self> {
+		info!("Setting up file watcher for {:?}", files);
 		let (sender, _) = tokio::sync::broadcast::channel(10);
-		let sender_2 = sender.clone();
+		let (event_sender, events) = std::sync::mpsc::channel();
 
 		let watcher = RecommendedWatcher::new(
-			move |res| match res {
-				Ok(notify::Event {
-					kind: notify::EventKind::Modify(_),
-					..
-				}) => {
-					// info!("Sending watcher update");
-					if sender_2.send(()).is_err() {
-						error!("File watcher received end has been dropped");
-					}
-				}
-				Err(e) => {
-					error!("File watcher error: {:?}", e);
-				}
-				_ => (),
+			move |res| {
+				// Fails only once the debouncer is gone along with the watcher
+				let _ = event_sender.send(res);
 			},
 			notify::Config::default(),
 		);
-
-		let mut watcher = match watcher {
-			Ok(v) => v,
+		let watcher = match watcher {
+			Ok(v) => Arc::new(Mutex::new(v)),
 			Err(e) => {
 				anyhow::bail!("Failed to create file watcher: {:?}", e);
 			}
 		};
 
-		if let Err(e) = watcher.watch(&path, RecursiveMode::Recursive) {
-			anyhow::bail!("Failed to create file watcher: {:?}", e);
-		}
-
-		// let _ = receiver.changed().await?;
-		info!("File watcher set up successfully");
-
-		Ok(FileWatch {
+		let file_watch = FileWatch {
 			watcher,
+			watched: Default::default(),
 			sender,
 			id: rand::random(),
-		})
+		};
+		file_watch.set_files(files)?;
+
+		let watcher = Arc::downgrade(&file_watch.watcher);
+		let watched = file_watch.watched.clone();
+		let sender = file_watch.sender.clone();
+		std::thread::spawn(move || Self::debounce(events, watcher, watched, sender));
+
+		info!("File watcher set up successfully");
+		Ok(file_watch)
+	}
+
+	/// Replaces the watched files. Files in directories that don't exist are skipped.
+	pub fn set_files(&self, files: Vec<PathBuf>) -> Result<()> {
+		let mut files_in_dirs = HashSet::new();
+		for file in files {
+			match watched_path(&file) {
+				Some(v) => {
+					files_in_dirs.insert(v);
+				}
+				None => warn!("Not watching {}, its directory is missing", file.display()),
+			}
+		}
+
+		// Locked in the same order as when re-arming
+		let mut watcher = self.watcher.lock().unwrap();
+		let mut watched = self.watched.lock().unwrap();
+		watched.files = files_in_dirs;
+		Self::watch_dirs(&mut watcher, &mut watched, false)
+	}
+
+	/// Watches the directories of the watched files. `rewatch` also watches the ones that
+	/// already are again, which re-arms watches broken by an error.
+	fn watch_dirs(
+		watcher: &mut RecommendedWatcher,
+		watched: &mut WatchedFiles,
+		rewatch: bool,
+	) -> Result<()> {
+		let dirs = watched
+			.files
+			.iter()
+			.filter_map(|file| file.parent())
+			.map(PathBuf::from)
+			.collect::<HashSet<_>>();
+		for dir in watched.dirs.difference(&dirs) {
+			if let Err(e) = watcher.unwatch(dir) {
+				warn!("Failed to stop watching {}: {:?}", dir.display(), e);
+			}
+		}
+		watched.dirs.retain(|dir| dirs.contains(dir));
+
+		for dir in dirs {
+			if watched.dirs.contains(&dir) {
+				if !rewatch {
+					continue;
+				}
+				let _ = watcher.unwatch(&dir);
+				watched.dirs.remove(&dir);
+			}
+			if let Err(e) = watcher.watch(&dir, RecursiveMode::NonRecursive) {
+				anyhow::bail!("Failed to watch {}: {:?}", dir.display(), e);
+			}
+			watched.dirs.insert(dir);
+		}
+		Ok(())
+	}
+
+	/// Turns each burst of events about watched files into a single update. Runs until the
+	/// watcher is dropped.
+	fn debounce(
+		events: std::sync::mpsc::Receiver<notify::Result<notify::Event>>,
+		watcher: Weak<Mutex<RecommendedWatcher>>,
+		watched: Arc<Mutex<WatchedFiles>>,
+		sender: tokio::sync::broadcast::Sender<ChangedFiles>,
+	) {
+		use std::sync::mpsc::RecvTimeoutError;
+
+		let collect = |changed: &mut ChangedFiles, event: notify::Result<notify::Event>| match event
+		{
+			Ok(event) if event.need_rescan() => *changed = None,
+			Ok(notify::Event {
+				kind: notify::EventKind::Access(_),
+				..
+			}) => (),
+			Ok(event) => {
+				if let Some(changed) = changed {
+					let files = &watched.lock().unwrap().files;
+					changed.extend(event.paths.into_iter().filter(|path| files.contains(path)));
+				}
+			}
+			Err(e) => {
+				error!("File watcher error: {:?}", e);
+				Self::rearm(&watcher, &watched);
+				// Changes may have been missed
+				*changed = None;
+			}
+		};
+
+		while let Ok(event) = events.recv() {
+			let mut changed = Some(HashSet::new());
+			collect(&mut changed, event);
+			loop {
+				match events.recv_timeout(FILE_WATCH_DEBOUNCE) {
+					Ok(event) => collect(&mut changed, event),
+					Err(RecvTimeoutError::Timeout) => break,
+					Err(RecvTimeoutError::Disconnected) => return,
+				}
+			}
+			let is_empty = matches!(changed, Some(ref files) if files.is_empty());
+			if !is_empty && sender.send(changed).is_err() {
+				error!("File watcher received end has been dropped");
+			}
+		}
+	}
+
+	/// Watches the directories again until it succeeds or the watcher is dropped
+	fn rearm(watcher: &Weak<Mutex<RecommendedWatcher>>, watched: &Mutex<WatchedFiles>) {
+		loop {
+			std::thread::sleep(FILE_WATCH_REARM_DELAY);
+			let watcher = match watcher.upgrade() {
+				Some(v) => v,
+				None => return,
+			};
+			let result = Self::watch_dirs(
+				&mut watcher.lock().unwrap(),
+				&mut watched.lock().unwrap(),
+				true,
+			);
+			match result {
+				Ok(()) => {
+					info!("File watcher re-armed");
+					return;
+				}
+				Err(e) => warn!("Failed to re-arm file watcher: {:?}", e),
+			}
+		}
 	}
 
 	pub fn recipe(&self) -> FileWatchRecipe {
@@ -336,13 +1962,13 @@ impl FileWatch {
 	}
 }
 
-pub struct FileWatchRecipe(usize, tokio::sync::broadcast::Sender<()>);
+pub struct FileWatchRecipe(usize, tokio::sync::broadcast::Sender<ChangedFiles>);
 
 impl<H, E> iced_futures::subscription::Recipe<H, E> for FileWatchRecipe
 where
 	H: std::hash::Hasher,
 {
-	type Output = ();
+	type Output = ChangedFiles;
 
 	fn hash(&self, state: &mut H) {
 		use std::hash::Hash;
@@ -362,72 +1988,384 @@ where
 }
 
 impl App {
+	/// Loads the current presentation again
+	fn load(&self) -> Command<Message> {
+		Command::perform(
+			commands::load(self.path.clone(), self.image_cache.clone(), self.canvas),
+			Message::Loaded,
+		)
+	}
+
+	/// Loads the presentation at `path` in place of the current one, which stays if that fails
+	fn open(&self, path: PathBuf) -> Command<Message> {
+		info!("Opening {}", path.display());
+		Command::perform(
+			commands::load(Some(path), self.image_cache.clone(), self.canvas),
+			Message::Loaded,
+		)
+	}
+
+	/// Opens the recent presentation at `idx` of the welcome screen, or the example after them
+	fn open_welcome_entry(&self, idx: usize) -> Command<Message> {
+		match self.recent.entries.get(idx) {
+			Some(entry) => self.open(entry.path.clone()),
+			None => {
+				info!("Opening the example presentation");
+				Command::perform(
+					commands::load(None, self.image_cache.clone(), self.canvas),
+					Message::Loaded,
+				)
+			}
+		}
+	}
+
+	/// Forgets the recent presentation at `idx`, the example stays
+	fn remove_welcome_entry(&mut self, idx: usize) {
+		if !self.recent.remove(idx) {
+			return;
+		}
+		self.recent.save();
+		if let Stage::WelcomeScreen(ref mut welcome) = self.stage {
+			// Stays on the entry that took the place of the removed one
+			welcome.move_selection(0, self.recent.entries.len() + 1);
+		}
+	}
+
+	/// State of a newly opened presentation, at `start_slide` if given. Otherwise at or offering
+	/// the slide it was left at.
+	fn resume_state(
+		&self,
+		presentation: &Presentation,
+		start_slide: Option<usize>,
+	) -> PresentationState {
+		let mut state = PresentationState::default();
+		if let Some(idx) = start_slide {
+			let last_idx = presentation.slides.len().saturating_sub(1);
+			if idx > last_idx {
+				warn!(
+					"Slide {} doesn't exist, starting at the last one, {}",
+					idx + 1,
+					last_idx + 1
+				);
+			}
+			state.slide_idx = idx.min(last_idx);
+			return state;
+		}
+
+		let last_slide = presentation
+			.canonical_source
+			.as_ref()
+			.and_then(|path| self.last_slides.get(path))
+			// The deck may have lost slides since
+			.filter(|idx| (1..presentation.slides.len()).contains(idx));
+		match last_slide {
+			Some(idx) if self.options.resume => {
+				info!("Resuming at slide {}", idx + 1);
+				state.slide_idx = idx;
+			}
+			Some(idx) => state.resume_offer = Some(idx),
+			None => (),
+		}
+		state
+	}
+
+	/// Stores the current slide, so that the presentation can be resumed at it
+	fn save_last_slide(&mut self) {
+		let (presentation, state) = match self.stage {
+			Stage::Presentation {
+				ref presentation,
+				ref state,
+				..
+			} => (presentation, state),
+			Stage::WelcomeScreen(_) => return,
+		};
+		// The slide that's offered isn't overwritten until the offer is taken or dismissed
+		if state.resume_offer.is_some() || self.saved_slide == Some(state.slide_idx) {
+			return;
+		}
+		let path = match presentation.canonical_source {
+			Some(ref v) => v.clone(),
+			None => return,
+		};
+		self.saved_slide = Some(state.slide_idx);
+		if self.last_slides.set(path, state.slide_idx) {
+			self.last_slides.save();
+		}
+	}
+
+	/// Puts the presentation on top of the recent ones
+	fn remember(&mut self, presentation: &Presentation) {
+		let path = match presentation.canonical_source {
+			Some(ref v) => v.clone(),
+			None => return,
+		};
+		let title = presentation
+			.slides
+			.first()
+			.and_then(Slide::first_header)
+			.unwrap_or(&presentation.title);
+		let entry = recent::RecentPresentation {
+			path,
+			title: title.to_string(),
+			slide_count: presentation.slides.len(),
+		};
+		if self.recent.add(entry) {
+			self.recent.save();
+		}
+	}
+
 	fn handle_keyboard_event(&mut self, kb_ev: keyboard::Event) -> Command<Message> {
-		use keyboard::{Event::*, KeyCode};
-		match kb_ev {
+		use config::{Action, KeyContext};
+		use keyboard::Event::*;
+
+		let (key_code, modifiers) = match kb_ev {
 			KeyPressed {
-				key_code: KeyCode::Space,
-				modifiers: _,
-			}
-			| KeyPressed {
-				key_code: KeyCode::X,
-				modifiers: _,
+				key_code,
+				modifiers,
+			} => (key_code, modifiers),
+			_ => return Command::none(),
+		};
+		// Modifiers are held for other keys, on their own they'd dismiss the resume offer or the
+		// typed slide number
+		if is_modifier(key_code) {
+			return Command::none();
+		}
+		let selection_action = self
+			.key_bindings
+			.action(KeyContext::Selection, key_code, modifiers);
+
+		if let Stage::Presentation {
+			ref presentation,
+			ref mut state,
+			..
+		} = self.stage
+		{
+			if state.overview.is_some() {
+				if let Some(action) = selection_action {
+					Self::handle_overview_action(presentation, state, action);
+				}
+				return Command::none();
 			}
-			| KeyPressed {
-				key_code: KeyCode::Right,
-				modifiers: _,
-			} => {
-				if let Stage::Presentation {
-					ref presentation,
-					ref mut state,
-					..
-				} = self.stage
-				{
-					state.slide_idx = (state.slide_idx + 1).min(presentation.slides.len() - 1);
+		}
+
+		if let Stage::WelcomeScreen(ref mut welcome) = self.stage {
+			// The example comes after the recent presentations
+			let entry_count = self.recent.entries.len() + 1;
+			match selection_action {
+				Some(Action::SelectUp) => {
+					welcome.move_selection(-1, entry_count);
+					return Command::none();
+				}
+				Some(Action::SelectDown) => {
+					welcome.move_selection(1, entry_count);
+					return Command::none();
 				}
+				Some(Action::Confirm) => {
+					let idx = welcome.selected;
+					return self.open_welcome_entry(idx);
+				}
+				Some(Action::Remove) => {
+					let idx = welcome.selected;
+					self.remove_welcome_entry(idx);
+					return Command::none();
+				}
+				_ => (),
 			}
+		}
 
-			KeyPressed {
-				key_code: KeyCode::Backspace,
-				modifiers: _,
+		let action = self
+			.key_bindings
+			.action(KeyContext::Slides, key_code, modifiers);
+		if let Stage::Presentation {
+			ref presentation,
+			ref mut state,
+			..
+		} = self.stage
+		{
+			if let Some(idx) = state.resume_offer.take() {
+				// Other keys dismiss the offer and do what they usually do
+				if selection_action == Some(Action::Confirm) {
+					state.jump_to(idx, presentation);
+					return Command::none();
+				}
 			}
-			| KeyPressed {
-				key_code: KeyCode::Z,
-				modifiers: _,
+			// Digit keys bound to an action don't type slide numbers
+			if let (Some(digit), None) = (digit(key_code), action) {
+				state.type_digit(digit);
+				return Command::none();
 			}
-			| KeyPressed {
-				key_code: KeyCode::Left,
-				modifiers: _,
-			} => {
-				if let Stage::Presentation {
-					presentation: _,
-					ref mut state,
-					..
-				} = self.stage
-				{
-					state.slide_idx = state.slide_idx.saturating_sub(1);
+			if state.typed_slide_number.is_some() {
+				match selection_action {
+					Some(Action::Confirm) => state.jump_to_typed(presentation),
+					_ => state.typed_slide_number = None,
 				}
+				return Command::none();
 			}
+		}
 
-			KeyPressed {
-				key_code: KeyCode::F,
-				modifiers: _,
-			} => match self.mode {
+		match action {
+			Some(action) => self.perform(action),
+			None => Command::none(),
+		}
+	}
+
+	fn handle_mouse_event(&mut self, mouse_ev: mouse::Event) -> Command<Message> {
+		if !self.settings.mouse_navigation {
+			return Command::none();
+		}
+		// Clicks and scrolling that the overview grid didn't handle are ignored too
+		if let Stage::Presentation {
+			state: PresentationState {
+				overview: Some(_), ..
+			},
+			..
+		} = self.stage
+		{
+			return Command::none();
+		}
+
+		let action = match mouse_ev {
+			mouse::Event::ButtonPressed(mouse::Button::Left) => Some(config::Action::Next),
+			mouse::Event::ButtonPressed(mouse::Button::Right) => Some(config::Action::Previous),
+			mouse::Event::WheelScrolled { delta } => self.wheel.scroll(delta, Instant::now()),
+			_ => None,
+		};
+		match action {
+			Some(action) => self.perform(action),
+			None => Command::none(),
+		}
+	}
+
+	fn perform(&mut self, action: config::Action) -> Command<Message> {
+		use config::Action;
+
+		match action {
+			Action::ToggleFullscreen => match self.mode {
 				ref mut mode @ Mode::Windowed => *mode = Mode::Fullscreen,
 				ref mut mode @ Mode::Fullscreen => *mode = Mode::Windowed,
 				Mode::Hidden => (),
 			},
-
+			Action::ExitFullscreen => {
+				if self.mode == Mode::Fullscreen {
+					self.mode = Mode::Windowed;
+				}
+			}
+			Action::Open => return Command::perform(commands::pick_file(), Message::FilePicked),
 			_ => (),
 		}
+
+		let (presentation, state) = match self.stage {
+			Stage::Presentation {
+				ref presentation,
+				ref mut state,
+				..
+			} => (presentation, state),
+			Stage::WelcomeScreen(_) => return Command::none(),
+		};
+		state.resume_offer = None;
+		let navigates = matches!(
+			action,
+			Action::Next | Action::Previous | Action::First | Action::Last | Action::JumpBack
+		);
+		if navigates && state.blank.is_some() {
+			// Brings the slide back without moving on
+			state.blank = None;
+			return Command::none();
+		}
+
+		match action {
+			Action::Next => state.next(presentation),
+			Action::Previous => state.previous(presentation),
+			Action::First => state.jump_to(0, presentation),
+			Action::Last => state.jump_to(usize::MAX, presentation),
+			Action::JumpBack => state.jump_back(presentation),
+			Action::Overview => state.overview = Some(Overview::new(state.slide_idx)),
+			Action::Blackout => state.toggle_blank(Blank::Black),
+			Action::Whiteout => state.toggle_blank(Blank::White),
+			Action::ToggleFullscreen | Action::ExitFullscreen | Action::Open => (),
+			// Only bound in the selection context
+			Action::SelectLeft
+			| Action::SelectRight
+			| Action::SelectUp
+			| Action::SelectDown
+			| Action::Confirm
+			| Action::Close
+			| Action::Remove => (),
+		}
 		Command::none()
 	}
+
+	fn handle_overview_action(
+		presentation: &Presentation,
+		state: &mut PresentationState,
+		action: config::Action,
+	) {
+		use config::Action;
+
+		let slide_count = presentation.slides.len();
+		let overview = match state.overview {
+			Some(ref mut v) => v,
+			None => return,
+		};
+		let columns = OVERVIEW_COLUMNS as isize;
+		match action {
+			Action::SelectLeft => overview.move_selection(-1, slide_count),
+			Action::SelectRight => overview.move_selection(1, slide_count),
+			Action::SelectUp => overview.move_selection(-columns, slide_count),
+			Action::SelectDown => overview.move_selection(columns, slide_count),
+			Action::Confirm => {
+				let idx = overview.selected;
+				state.overview = None;
+				state.jump_to(idx, presentation);
+			}
+			Action::Close => state.overview = None,
+			_ => (),
+		}
+	}
+}
+
+fn is_modifier(key_code: keyboard::KeyCode) -> bool {
+	use keyboard::KeyCode::*;
+
+	matches!(
+		key_code,
+		LShift | RShift | LControl | RControl | LAlt | RAlt | LWin | RWin
+	)
+}
+
+fn digit(key_code: keyboard::KeyCode) -> Option<usize> {
+	use keyboard::KeyCode::*;
+
+	let digit = match key_code {
+		Key0 | Numpad0 => 0,
+		Key1 | Numpad1 => 1,
+		Key2 | Numpad2 => 2,
+		Key3 | Numpad3 => 3,
+		Key4 | Numpad4 => 4,
+		Key5 | Numpad5 => 5,
+		Key6 | Numpad6 => 6,
+		Key7 | Numpad7 => 7,
+		Key8 | Numpad8 => 8,
+		Key9 | Numpad9 => 9,
+		_ => return None,
+	};
+	Some(digit)
 }
 
 #[derive(Debug)]
 pub enum Message {
-	Loaded(commands::LoadFromArgsResult),
+	Loaded(commands::LoadResult),
 	FileWatcherStarted(commands::StartFileWatcherResult),
-	Reloaded,
+	Reloaded(ChangedFiles),
+	ImagesReloaded(commands::ReloadImagesResult),
+	FilePicked(Option<PathBuf>),
+	FileDropped(PathBuf),
 	KeyboardEvent(keyboard::Event),
+	MouseEvent(mouse::Event),
+	OverviewSelected(usize),
+	WelcomeSelected(usize),
+	WelcomeRemoved(usize),
+	Tick(Instant),
+	WindowResized(Size),
+	CloseRequested,
 }
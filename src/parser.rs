@@ -1,20 +1,26 @@
 use anyhow::Result;
 use nom::branch::alt;
-use nom::bytes::complete::{is_not, tag, take_until, take_while_m_n};
-use nom::character::complete::{char, digit1, space0, space1};
-use nom::combinator::{map, map_res, opt};
+use nom::bytes::complete::{is_not, tag, take_while1, take_while_m_n};
+use nom::character::complete::{char, digit1, multispace0, space0, space1};
+use nom::combinator::{map, opt, peek};
 use nom::error::ParseError;
-use nom::sequence::{delimited, preceded, tuple};
+use nom::sequence::{delimited, preceded, separated_pair, terminated, tuple};
 use nom::{FindSubstring, IResult, InputTake, Parser};
 
 use crate::{
-	CodeBlockParams, CodeFontStyle, HeaderSize, Image, ImageParams, Language, Presentation, Slide,
-	SlideNode,
+	CodeBlockParams, FrontMatter, HeaderSize, Image, ImageParams, Language, Node, NodeParams,
+	Presentation, Slide, SlideNode, SlideParams,
 };
 use log::warn;
-use nom::multi::many1;
-use std::num::ParseFloatError;
+use nom::multi::{many0, many1};
 use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Problems that don't stop parsing, kept in `Presentation::warnings` for the `check` command to
+/// report. Parsers return them along with what they parsed, so the warnings of an alternative
+/// that isn't taken in the end are dropped with it.
+type Warnings = Vec<String>;
 
 #[cfg(test)]
 static SAMPLE_PRESENTATION: &str = r######"## Wprowadzenie do Rusta, dla tych, którzy już trochę programować umieją
@@ -99,14 +105,17 @@ fn parse_numbered_list(input: &str) -> IResult<&str, Vec<String>> {
 	Ok((tail, items))
 }
 
-fn parse_code_block(input: &str) -> IResult<&str, (Language, CodeBlockParams, String)> {
-	let (tail, code_block_params) = parse_code_block_params(input)?;
+fn parse_code_block(input: &str) -> IResult<&str, (Language, CodeBlockParams, String, Warnings)> {
+	let (tail, (code_block_params, mut warnings)) = parse_code_block_params(input)?;
 	let (tail, _) = tag("```")(tail)?;
 	let (tail, language) = till_pat_consuming("\n").parse(tail)?;
 	let language = match language.parse::<Language>() {
 		Ok(v) => v,
 		Err(_) => {
-			warn!("Unknown lang \"{}\". Defaulting to plain text", language);
+			warnings.push(format!(
+				"Unknown lang \"{}\". Defaulting to plain text",
+				language
+			));
 			Language::PlainText
 		}
 	};
@@ -129,104 +138,125 @@ fn parse_code_block(input: &str) -> IResult<&str, (Language, CodeBlockParams, St
 
 	let (tail, _) = till_pat_consuming("\n\n").parse(tail)?;
 
-	Ok((tail, (language, code_block_params, code_block.to_string())))
+	Ok((
+		tail,
+		(
+			language,
+			code_block_params,
+			code_block.to_string(),
+			warnings,
+		),
+	))
 }
 
-fn parse_code_block_params(mut input: &str) -> IResult<&str, CodeBlockParams> {
-	let mut params = CodeBlockParams::default();
+fn parse_code_block_params(input: &str) -> IResult<&str, (CodeBlockParams, Warnings)> {
+	let (tail, attributes) = parse_pipe_attributes(input)?;
 
-	loop {
-		let tail = match char::<_, nom::error::Error<&str>>('|')(input) {
-			Ok((tail, _)) => tail,
-			Err(_) => return Ok((input, params)),
-		};
-		let (tail, _) = space1(tail)?;
+	let mut params = CodeBlockParams::default();
+	let mut warnings = Warnings::new();
+	for (key, value) in attributes {
+		if !apply_code_block_attribute(&mut params, key, value, &mut warnings) {
+			warnings.push(format!("Unknown code block param \"{}\"", key));
+		}
+	}
 
-		let (tail, _) = alt((
-			preceded(
-				tuple((tag("font_size:"), space1)),
-				map(map_res(digit1, str::parse::<u16>), |font_size| {
-					params.font_size = Some(font_size);
-				}),
-			),
-			preceded(
-				tuple((tag("font_style:"), space1)),
-				map(
-					alt((
-						map(tag("regular"), |_| CodeFontStyle::Regular),
-						map(tag("bold"), |_| CodeFontStyle::Bold),
-						map(tag("semi_bold"), |_| CodeFontStyle::SemiBold),
-						map(tag("light"), |_| CodeFontStyle::Light),
-						map(tag("semi_light"), |_| CodeFontStyle::SemiLight),
-						map(tag("extra_light"), |_| CodeFontStyle::ExtraLight),
-					)),
-					|font_style| {
-						params.font_style = Some(font_style);
-					},
-				),
-			),
-			preceded(
-				tuple((tag("block_terminator:"), space1)),
-				map(take_until("\n"), |b: &str| {
-					params.block_terminator = Some(b.to_string());
-				}),
-			),
-		))(tail)?;
+	Ok((tail, (params, warnings)))
+}
 
-		let (tail, _) = till_pat_consuming("\n").parse(tail)?;
-		input = tail;
+fn apply_code_block_attribute(
+	params: &mut CodeBlockParams,
+	key: &str,
+	value: &str,
+	warnings: &mut Warnings,
+) -> bool {
+	match key {
+		"font_size" => {
+			params.font_size = parse_attribute_value(key, value, warnings).or(params.font_size)
+		}
+		"font_style" => {
+			params.font_style =
+				parse_attribute_value(key, value, warnings).or(params.font_style.take())
+		}
+		"block_terminator" => params.block_terminator = Some(value.to_string()),
+		_ => return false,
 	}
+	true
 }
 
-fn parse_image(input: &str) -> IResult<&str, Image> {
+fn parse_image(input: &str) -> IResult<&str, (Image, Warnings)> {
 	let (tail, _) = char('!')(input)?;
 	let (tail, alt_text) = delimited(char('['), opt(is_not("]")), char(']'))(tail)?;
 	let (tail, path) = delimited(char('('), is_not(")"), char(')'))(tail)?;
-	let (tail, params) = parse_image_params(tail)?;
+	let (tail, (params, warnings)) = parse_image_params(tail)?;
 
 	let (tail, _) = till_pat_consuming("\n\n").parse(tail)?;
 
 	Ok((
 		tail,
-		Image {
-			path: path.to_string(),
-			alt_text: alt_text.map(ToString::to_string).unwrap_or_default(),
-			params,
-			handle: None,
-		},
+		(
+			Image {
+				path: path.to_string(),
+				alt_text: alt_text.map(ToString::to_string).unwrap_or_default(),
+				params,
+				handle: None,
+				animation: None,
+				vector: None,
+				source_size: None,
+				error: None,
+			},
+			warnings,
+		),
 	))
 }
 
-fn parse_image_params(mut input: &str) -> IResult<&str, ImageParams> {
-	match preceded::<_, _, _, nom::error::Error<&str>, _, _>(space0, char('{'))(input) {
-		Ok((tail, _)) => input = tail,
-		Err(_) => return Ok((input, ImageParams::default())),
+fn parse_image_params(input: &str) -> IResult<&str, (ImageParams, Warnings)> {
+	let mut params = ImageParams::default();
+	let mut warnings = Warnings::new();
+
+	let (tail, attributes) = match parse_attributes(input) {
+		Ok(v) => v,
+		Err(_) => return Ok((input, (params, warnings))),
+	};
+	for (key, value) in attributes {
+		if !apply_image_attribute(&mut params, key, value, &mut warnings) {
+			warnings.push(format!("Unknown image param \"{}\"", key));
+		}
 	}
 
-	let (mut input, scale) = opt(delimited(
-		space0,
-		map(
-			tuple((
-				tag("scale:"),
-				preceded(
-					space0,
-					map_res(digit1, |scale: &str| -> Result<_, ParseFloatError> {
-						scale.parse::<f32>()
-					}),
-				),
-				char('%'),
-			)),
-			|(_, scale, _)| scale,
-		),
-		tuple((space0, char(';'), space0)),
-	))(input)?;
+	Ok((tail, (params, warnings)))
+}
 
-	match preceded::<_, _, _, nom::error::Error<&str>, _, _>(space0, char('}'))(input) {
-		Ok((tail, _)) => input = tail,
-		Err(_) => return Ok((input, ImageParams::default())),
+fn apply_image_attribute(
+	params: &mut ImageParams,
+	key: &str,
+	value: &str,
+	warnings: &mut Warnings,
+) -> bool {
+	match key {
+		"scale" => match value
+			.strip_suffix('%')
+			.and_then(|scale| scale.trim().parse::<f32>().ok())
+		{
+			Some(scale) => params.scale = Some(scale / 100.0),
+			None => warnings.push(format!("Invalid value \"{}\" of \"{}\"", value, key)),
+		},
+		"width" => params.width = parse_attribute_value(key, value, warnings),
+		"height" => params.height = parse_attribute_value(key, value, warnings),
+		"max_width" => params.max_width = parse_attribute_value(key, value, warnings),
+		"max_height" => params.max_height = parse_attribute_value(key, value, warnings),
+		"float" => params.float = parse_attribute_value(key, value, warnings),
+		"crop" => params.crop = parse_attribute_value(key, value, warnings),
+		"corner_radius" => {
+			params.corner_radius =
+				parse_attribute_value(key, value.strip_suffix("px").unwrap_or(value), warnings)
+		}
+		"caption" => params.caption = parse_attribute_value(key, value, warnings),
+		"playback" => {
+			params.playback = parse_attribute_value(key, value, warnings).unwrap_or_default()
+		}
+		_ => return false,
 	}
-
-	Ok((input, ImageParams { scale }))
+	true
 }
 
 fn parse_comment(input: &str) -> IResult<&str, String> {
@@ -242,86 +272,370 @@ fn parse_text_section(input: &str) -> IResult<&str, String> {
 	Ok((tail, text_str.trim().to_string()))
 }
 
-fn parse_slide_node(input: &str) -> IResult<&str, SlideNode> {
+fn parse_slide_node(input: &str) -> IResult<&str, (SlideNode, Warnings)> {
 	alt((
 		map(parse_header, |(header_size, header)| {
-			SlideNode::Header(header_size, header)
+			(SlideNode::Header(header_size, header), Warnings::new())
 		}),
 		map(parse_unnumbered_list, |items| {
-			SlideNode::UnnumberedList(items)
+			(SlideNode::UnnumberedList(items), Warnings::new())
 		}),
-		map(parse_numbered_list, |items| SlideNode::NumberedList(items)),
-		map(parse_code_block, |(language, params, code_block)| {
-			SlideNode::CodeBlock(language, params, code_block)
+		map(parse_numbered_list, |items| {
+			(SlideNode::NumberedList(items), Warnings::new())
 		}),
-		map(parse_image, |image| SlideNode::Image(image)),
-		map(parse_comment, |text| SlideNode::Comment(text)),
-		map(parse_text_section, |text| SlideNode::Text(text)),
+		map(
+			parse_code_block,
+			|(language, params, code_block, warnings)| {
+				(SlideNode::CodeBlock(language, params, code_block), warnings)
+			},
+		),
+		map(parse_image, |(image, warnings)| {
+			(SlideNode::Image(image), warnings)
+		}),
+		map(parse_comment, |text| {
+			(SlideNode::Comment(text), Warnings::new())
+		}),
+		map(parse_text_section, |text| {
+			(SlideNode::Text(text), Warnings::new())
+		}),
+	))(input)
+}
+
+/// Parses `300ms`, `1s` or `0.5s`
+fn parse_duration(input: &str) -> Option<Duration> {
+	let input = input.trim();
+	let millis = match input.strip_suffix("ms") {
+		Some(millis) => millis.trim().parse::<f64>().ok()?,
+		None => input.strip_suffix('s')?.trim().parse::<f64>().ok()? * 1000.0,
+	};
+	if !millis.is_finite() || millis < 0.0 {
+		return None;
+	}
+	Some(Duration::from_millis(millis.round() as u64))
+}
+
+fn parse_attribute_key(input: &str) -> IResult<&str, &str> {
+	take_while1(|c: char| c.is_alphanumeric() || c == '_')(input)
+}
+
+/// Parses a `{ key: value; other_key: value; }` block into a list of key value pairs.
+///
+/// This is the attribute syntax shared by slides (after the `---` divider), nodes (in a line
+/// preceding the node) and images (right after the image). The last `;` is optional.
+fn parse_attributes(input: &str) -> IResult<&str, Vec<(&str, &str)>> {
+	let (tail, _) = preceded(space0, char('{'))(input)?;
+	let (tail, attributes) = many0(delimited(
+		space0,
+		separated_pair(
+			parse_attribute_key,
+			tuple((space0, char(':'), space0)),
+			map(is_not(";}\n"), str::trim),
+		),
+		alt((
+			preceded(space0, char(';')),
+			peek(preceded(space0, char('}'))),
+		)),
+	))(tail)?;
+	let (tail, _) = preceded(space0, char('}'))(tail)?;
+
+	Ok((tail, attributes))
+}
+
+/// Parses `| key: value` lines, the older syntax for code block params
+fn parse_pipe_attributes(input: &str) -> IResult<&str, Vec<(&str, &str)>> {
+	many0(preceded(
+		tuple((char('|'), space1)),
+		separated_pair(
+			parse_attribute_key,
+			tuple((space0, char(':'), space0)),
+			map(till_pat_consuming("\n"), str::trim),
+		),
 	))(input)
 }
 
-fn parse_slide_divider(input: &str) -> IResult<&str, Option<Image>> {
+/// Invalid values are reported as warnings, so that a typo doesn't fail the whole presentation
+fn parse_attribute_value<T: FromStr>(key: &str, value: &str, warnings: &mut Warnings) -> Option<T> {
+	match value.parse::<T>() {
+		Ok(v) => Some(v),
+		Err(_) => {
+			warnings.push(format!("Invalid value \"{}\" of \"{}\"", value, key));
+			None
+		}
+	}
+}
+
+fn parse_slide_params(input: &str) -> IResult<&str, (SlideParams, Warnings)> {
+	let (tail, attributes) = parse_attributes(input)?;
+
+	let mut params = SlideParams::default();
+	let mut warnings = Warnings::new();
+	for (key, value) in attributes {
+		match key {
+			"transition" => params.transition = parse_attribute_value(key, value, &mut warnings),
+			"transition_duration" => match parse_duration(value) {
+				Some(duration) => params.transition_duration = Some(duration),
+				None => warnings.push(format!("Invalid transition duration \"{}\"", value)),
+			},
+			"footer" => params.footer = parse_attribute_value(key, value, &mut warnings),
+			"align" => params.align = parse_attribute_value(key, value, &mut warnings),
+			"valign" => params.valign = parse_attribute_value(key, value, &mut warnings),
+			"id" => params.id = Some(value.to_string()),
+			_ => warnings.push(format!("Unknown slide param \"{}\"", key)),
+		}
+	}
+
+	Ok((tail, (params, warnings)))
+}
+
+/// Applies attributes common to every kind of node. Returns `false` for unknown keys.
+fn apply_node_attribute(
+	params: &mut NodeParams,
+	key: &str,
+	value: &str,
+	warnings: &mut Warnings,
+) -> bool {
+	match key {
+		"align" => params.align = parse_attribute_value(key, value, warnings),
+		"size" => params.size = parse_attribute_value(key, value, warnings),
+		"color" => params.color = parse_attribute_value(key, value, warnings),
+		"fragment" => {
+			params.fragment = parse_attribute_value(key, value, warnings).unwrap_or(false)
+		}
+		"id" => params.id = Some(value.to_string()),
+		"class" => params.class = Some(value.to_string()),
+		_ => return false,
+	}
+	true
+}
+
+/// Parses a node along with the attribute line preceding it. Apart from the common node
+/// attributes, the line may contain attributes specific to the node kind, e.g. `font_size` of a
+/// code block or `scale` of an image. `block_terminator` is the exception, as it's needed while
+/// parsing the code block, so it has to be given in a `|` line.
+fn parse_node(input: &str) -> IResult<&str, (Node, Warnings)> {
+	let (tail, attributes) =
+		opt(terminated(parse_attributes, preceded(space0, char('\n'))))(input)?;
+	let (tail, (mut kind, mut warnings)) = parse_slide_node(tail)?;
+
+	let mut params = NodeParams::default();
+	for (key, value) in attributes.unwrap_or_default() {
+		if key == "block_terminator" {
+			warnings.push(
+				"block_terminator has to be given in a `| block_terminator: ...` line".to_string(),
+			);
+			continue;
+		}
+		let known = apply_node_attribute(&mut params, key, value, &mut warnings)
+			|| match kind {
+				SlideNode::CodeBlock(_, ref mut code_block_params, _) => {
+					apply_code_block_attribute(code_block_params, key, value, &mut warnings)
+				}
+				SlideNode::Image(ref mut image) => {
+					apply_image_attribute(&mut image.params, key, value, &mut warnings)
+				}
+				_ => false,
+			};
+		if !known {
+			warnings.push(format!("Unknown attribute \"{}\"", key));
+		}
+	}
+
+	Ok((tail, (Node { kind, params }, warnings)))
+}
+
+fn parse_front_matter(input: &str) -> IResult<&str, (FrontMatter, Warnings)> {
+	let mut front_matter = FrontMatter::default();
+	let mut warnings = Warnings::new();
+
+	let opening = alt((
+		tag::<_, _, nom::error::Error<&str>>("+++\n"),
+		tag("+++\r\n"),
+	));
+	let mut input = match opening.parse(input) {
+		Ok((tail, _)) => tail,
+		Err(_) => return Ok((input, (front_matter, warnings))),
+	};
+
+	loop {
+		if let Ok((tail, _)) = tag::<_, _, nom::error::Error<&str>>("+++")(input) {
+			let (tail, _) = till_pat_consuming("\n").parse(tail)?;
+			return Ok((tail, (front_matter, warnings)));
+		}
+		if input.is_empty() {
+			return Err(nom::Err::Failure(nom::error::Error::new(
+				input,
+				nom::error::ErrorKind::Eof,
+			)));
+		}
+
+		let (tail, line) = till_pat_consuming("\n").parse(input)?;
+		input = tail;
+
+		let (key, value) = match line.split_once(':') {
+			Some((key, value)) => (key.trim(), value.trim()),
+			None => {
+				if !line.trim().is_empty() {
+					warnings.push(format!("Invalid front matter line \"{}\"", line));
+				}
+				continue;
+			}
+		};
+		match key {
+			"transition" => {
+				front_matter.transition = parse_attribute_value(key, value, &mut warnings)
+			}
+			"transition_duration" => match parse_duration(value) {
+				Some(duration) => front_matter.transition_duration = Some(duration),
+				None => warnings.push(format!("Invalid transition duration \"{}\"", value)),
+			},
+			"footer" => {
+				front_matter.footer =
+					parse_attribute_value(key, value, &mut warnings).unwrap_or(false)
+			}
+			"footer_text" => front_matter.footer_text = Some(value.to_string()),
+			"align" => front_matter.align = parse_attribute_value(key, value, &mut warnings),
+			"valign" => front_matter.valign = parse_attribute_value(key, value, &mut warnings),
+			"captions" => {
+				front_matter.captions =
+					parse_attribute_value(key, value, &mut warnings).unwrap_or(false)
+			}
+			"figure_numbers" => {
+				front_matter.figure_numbers =
+					parse_attribute_value(key, value, &mut warnings).unwrap_or(false)
+			}
+			"theme" => {
+				front_matter.theme =
+					parse_attribute_value(key, value, &mut warnings).unwrap_or_default()
+			}
+			"author" => front_matter.author = Some(value.to_string()),
+			"event" => front_matter.event = Some(value.to_string()),
+			"date" => front_matter.date = Some(value.to_string()),
+			"header_font" => front_matter.header_font = Some(value.to_string()),
+			"body_font" => front_matter.body_font = Some(value.to_string()),
+			"code_font" => front_matter.code_font = Some(value.to_string()),
+			"fallback_fonts" => {
+				front_matter.fallback_fonts = value
+					.split(',')
+					.map(str::trim)
+					.filter(|path| !path.is_empty())
+					.map(ToString::to_string)
+					.collect()
+			}
+			_ => warnings.push(format!("Unknown front matter key \"{}\"", key)),
+		}
+	}
+}
+
+fn parse_slide_divider(input: &str) -> IResult<&str, (SlideParams, Option<Image>, Warnings)> {
 	let (tail, _) = tag("---")(input)?;
+	let (tail, params) = opt(parse_slide_params)(tail)?;
 	let (tail, background) = opt(parse_image)(tail)?;
-	Ok((tail, background))
+	let (tail, _) = match (&params, &background) {
+		(Some(_), None) => multispace0(tail)?,
+		_ => (tail, ""),
+	};
+
+	let (params, mut warnings) = params.unwrap_or_default();
+	let background = background.map(|(image, image_warnings)| {
+		warnings.extend(image_warnings);
+		image
+	});
+	Ok((tail, (params, background, warnings)))
 }
 
-fn parse_slide(mut input: &str) -> IResult<&str, Slide> {
+fn parse_slide(mut input: &str) -> IResult<&str, (Slide, Warnings)> {
 	let mut slide_nodes = Vec::new();
+	let mut warnings = Warnings::new();
 
 	let mut background = None;
+	let mut params = SlideParams::default();
 
 	while !input.is_empty() {
-		if let Ok((tail, new_background)) = parse_slide_divider(input) {
+		if let Ok((tail, (new_params, new_background, divider_warnings))) =
+			parse_slide_divider(input)
+		{
 			if slide_nodes.is_empty() {
+				warnings.extend(divider_warnings);
+				if let Some(ref image) = new_background {
+					warnings.push(format!(
+						"Slide backgrounds aren't supported yet, {} isn't shown",
+						image.path
+					));
+				}
 				background = new_background;
+				params = new_params;
 			} else {
+				// The divider starts the next slide, it's parsed again along with it
 				return Ok((
 					input,
-					Slide {
-						nodes: slide_nodes,
-						background,
-					},
+					(
+						Slide {
+							nodes: slide_nodes,
+							background,
+							params,
+						},
+						warnings,
+					),
 				));
 			}
 			input = tail;
 			continue;
 		}
-		let (tail, slide_node) = parse_slide_node(input)?;
+		let (tail, (node, node_warnings)) = parse_node(input)?;
 		input = tail;
-		slide_nodes.push(slide_node);
+		slide_nodes.push(node);
+		warnings.extend(node_warnings);
 	}
 
 	Ok((
 		input,
-		Slide {
-			nodes: slide_nodes,
-			background,
-		},
+		(
+			Slide {
+				nodes: slide_nodes,
+				background,
+				params,
+			},
+			warnings,
+		),
 	))
 }
 
-pub fn parse_slides(mut input: &str) -> IResult<&str, Vec<Slide>> {
+pub fn parse_slides(mut input: &str) -> IResult<&str, (Vec<Slide>, Warnings)> {
 	let mut slides = Vec::new();
+	let mut warnings = Warnings::new();
 
 	while !input.is_empty() {
-		let (tail, slide) = parse_slide(input)?;
+		let (tail, (slide, slide_warnings)) = parse_slide(input)?;
 		slides.push(slide);
+		warnings.extend(slide_warnings);
 		input = tail;
 	}
 
-	Ok((input, slides))
+	Ok((input, (slides, warnings)))
 }
 
 pub fn parse_presentation(title: String, path: PathBuf, input: &str) -> Result<Presentation> {
-	let (_, slides) = match parse_slides(&input) {
+	let (input, (front_matter, mut warnings)) = match parse_front_matter(&input) {
+		Ok(v) => v,
+		Err(e) => anyhow::bail!("parse_front_matter failed with: {:?}", e),
+	};
+	let (_, (slides, slide_warnings)) = match parse_slides(&input) {
 		Ok(v) => v,
 		Err(e) => anyhow::bail!("parse_presentation failed with: {:?}", e),
 	};
+	warnings.extend(slide_warnings);
+	for warning in &warnings {
+		warn!("{}", warning);
+	}
 	Ok(Presentation {
 		title,
 		path,
+		source: None,
+		canonical_source: None,
+		front_matter,
+		fonts: Default::default(),
 		slides,
+		warnings,
 	})
 }
 
@@ -330,7 +644,10 @@ mod tests {
 	use anyhow::Result;
 
 	use super::*;
-	use crate::{Image, Language};
+	use crate::{
+		CodeFontStyle, Crop, HorizontalAlign, Image, ImageFloat, ImageSize, Language, Rgb, Theme,
+		TransitionKind, VerticalAlign,
+	};
 
 	#[test]
 	fn parse_presentation_test() -> Result<()> {
@@ -364,19 +681,19 @@ mod tests {
 	fn parse_headers() -> Result<()> {
 		let expected = Slide {
 			nodes: vec![
-				SlideNode::Header(HeaderSize::Three, String::from("hi1")),
-				SlideNode::Header(HeaderSize::Two, String::from("Hello 2")),
+				SlideNode::Header(HeaderSize::Three, String::from("hi1")).into(),
+				SlideNode::Header(HeaderSize::Two, String::from("Hello 2")).into(),
 			],
 			..Default::default()
 		};
 
-		let (_, slide_nodes) = parse_slide("### hi1\n\n## Hello 2\n\n")?;
+		let (_, (slide_nodes, _)) = parse_slide("### hi1\n\n## Hello 2\n\n")?;
 		assert_eq!(slide_nodes, expected);
 
-		let (_, slide_nodes) = parse_slide("### hi1\n\n## Hello 2")?;
+		let (_, (slide_nodes, _)) = parse_slide("### hi1\n\n## Hello 2")?;
 		assert_eq!(slide_nodes, expected);
 
-		let (_, slide_nodes) = parse_slide("### hi1\n\n## Hello 2\n")?;
+		let (_, (slide_nodes, _)) = parse_slide("### hi1\n\n## Hello 2\n")?;
 		assert_eq!(slide_nodes, expected);
 
 		Ok(())
@@ -390,9 +707,13 @@ mod tests {
 			params: Default::default(),
 			// handle isn't compared
 			handle: None,
+			animation: None,
+			vector: None,
+			source_size: None,
+			error: None,
 		};
 
-		let (_, image) = super::parse_image("![Ferris the crab](ferris.png)")?;
+		let (_, (image, _)) = super::parse_image("![Ferris the crab](ferris.png)")?;
 		eprintln!("{:?}", image);
 		eprintln!("{:?}", expected);
 
@@ -408,9 +729,13 @@ mod tests {
 			params: Default::default(),
 			// handle isn't compared
 			handle: None,
+			animation: None,
+			vector: None,
+			source_size: None,
+			error: None,
 		};
 
-		let (_, image) = super::parse_image("![](ferris.png)")?;
+		let (_, (image, _)) = super::parse_image("![](ferris.png)")?;
 		assert_eq!(expected, image);
 
 		Ok(())
@@ -421,16 +746,49 @@ mod tests {
 		let expected = Image {
 			path: "ferris.png".to_string(),
 			alt_text: "Ferris the crab".to_string(),
-			params: ImageParams { scale: Some(0.5) },
+			params: ImageParams {
+				scale: Some(0.5),
+				..Default::default()
+			},
 			// handle isn't compared
 			handle: None,
+			animation: None,
+			vector: None,
+			source_size: None,
+			error: None,
 		};
 
-		let (_, image) = super::parse_image("![Ferris the crab](ferris.png){ scale: 50%; }")?;
+		let (_, (image, _)) = super::parse_image("![Ferris the crab](ferris.png){ scale: 50%; }")?;
 		eprintln!("{:?}", image);
 		eprintln!("{:?}", expected);
 
 		assert_eq!(image, expected);
+		assert_eq!(image.params, expected.params);
+		Ok(())
+	}
+
+	#[test]
+	fn parse_image_sizing_params() -> Result<()> {
+		let expected = ImageParams {
+			scale: Some(0.125),
+			width: Some(ImageSize::Percent(40.0)),
+			max_height: Some(ImageSize::Pixels(300.0)),
+			float: Some(ImageFloat::Right),
+			crop: Some(Crop {
+				x: 10,
+				y: 20,
+				width: 300,
+				height: 200,
+			}),
+			corner_radius: Some(16.0),
+			..Default::default()
+		};
+
+		let (_, (image, _)) = super::parse_image(
+			"![](ferris.png){ scale: 12.5%; width: 40%; max_height: 300px; float: right; crop: 10 20 300 200; corner_radius: 16px; }",
+		)?;
+
+		assert_eq!(image.params, expected);
 		Ok(())
 	}
 
@@ -449,6 +807,7 @@ fn main() {
 }
 "#
 			.to_string(),
+			Vec::new(),
 		);
 
 		let (_, code_block) = super::parse_code_block(
@@ -483,6 +842,7 @@ fn main() {
 }
 "#
 			.to_string(),
+			Vec::new(),
 		);
 
 		let (_, code_block) = super::parse_code_block(
@@ -536,6 +896,7 @@ fn main() {
 }
 "#
 			.to_string(),
+			Vec::new(),
 		);
 
 		let (_, code_block) = super::parse_code_block(
@@ -564,11 +925,12 @@ fn main() {
 				"Ala".to_string(),
 				"ma".to_string(),
 				"kota".to_string(),
-			])],
+			])
+			.into()],
 			..Default::default()
 		};
 
-		let (_, slide) = super::parse_slide(
+		let (_, (slide, _)) = super::parse_slide(
 			r#"-    Ala
 - ma
 - kota
@@ -587,11 +949,13 @@ fn main() {
 				"Ala".to_string(),
 				"ma".to_string(),
 				"kota".to_string(),
-			])],
+			])
+			.into()],
 			background: None,
+			params: Default::default(),
 		};
 
-		let (_, slide) = super::parse_slide(
+		let (_, (slide, _)) = super::parse_slide(
 			r#"1. Ala
 2. ma
 3.    kota
@@ -619,11 +983,12 @@ fn main() {
 }
 "#
 				.to_string(),
-			)],
+			)
+			.into()],
 			..Default::default()
 		};
 
-		let (_, slide) = super::parse_slide(
+		let (_, (slide, _)) = super::parse_slide(
 			r#"```rust
 enum Result<T, E> {
 	Ok(T),
@@ -662,11 +1027,12 @@ fn main() {
 }
 "#
 				.to_string(),
-			)],
+			)
+			.into()],
 			..Default::default()
 		};
 
-		let (_, slide) = super::parse_slide(
+		let (_, (slide, _)) = super::parse_slide(
 			r#"| font_style: semi_light
 | font_size: 98
 ```rust
@@ -695,11 +1061,16 @@ fn main() {
 				alt_text: "ferris".to_string(),
 				params: Default::default(),
 				handle: None,
-			})],
+				animation: None,
+				vector: None,
+				source_size: None,
+				error: None,
+			})
+			.into()],
 			..Default::default()
 		};
 
-		let (_, slide_nodes) = parse_slide("![ferris](ferris.png)\n\n")?;
+		let (_, (slide_nodes, _)) = parse_slide("![ferris](ferris.png)\n\n")?;
 		assert_eq!(slide_nodes, expected);
 
 		Ok(())
@@ -708,16 +1079,21 @@ fn main() {
 	#[test]
 	fn parse_slide_background() -> Result<()> {
 		let expected = Slide {
-			nodes: vec![SlideNode::Text("Hello, World!".to_string())],
+			nodes: vec![SlideNode::Text("Hello, World!".to_string()).into()],
 			background: Some(Image {
 				path: "assets/generic-background.jpg".to_string(),
 				alt_text: "".to_string(),
 				params: Default::default(),
 				handle: None,
+				animation: None,
+				vector: None,
+				source_size: None,
+				error: None,
 			}),
+			params: Default::default(),
 		};
 
-		let (_, slide) = parse_slide(
+		let (_, (slide, _)) = parse_slide(
 			r#"---![](assets/generic-background.jpg)
 
 Hello, World!
@@ -733,24 +1109,27 @@ Hello, World!
 	fn parse_slide_background_for_second_slide() -> Result<()> {
 		let expected = vec![
 			Slide {
-				nodes: vec![SlideNode::Header(
-					HeaderSize::One,
-					"first slide".to_string(),
-				)],
+				nodes: vec![SlideNode::Header(HeaderSize::One, "first slide".to_string()).into()],
 				background: None,
+				params: Default::default(),
 			},
 			Slide {
-				nodes: vec![SlideNode::Text("Hello, World!".to_string())],
+				nodes: vec![SlideNode::Text("Hello, World!".to_string()).into()],
 				background: Some(Image {
 					path: "assets/generic-background.jpg".to_string(),
 					alt_text: "".to_string(),
 					params: Default::default(),
 					handle: None,
+					animation: None,
+					vector: None,
+					source_size: None,
+					error: None,
 				}),
+				params: Default::default(),
 			},
 		];
 
-		let (_, slides) = parse_slides(
+		let (_, (slides, _)) = parse_slides(
 			r#"# first slide
 
 ---![](assets/generic-background.jpg)
@@ -764,6 +1143,261 @@ Hello, World!
 		Ok(())
 	}
 
+	#[test]
+	fn parse_slide_params() -> Result<()> {
+		let expected = vec![
+			Slide {
+				nodes: vec![SlideNode::Text("first".to_string()).into()],
+				..Default::default()
+			},
+			Slide {
+				nodes: vec![SlideNode::Text("second".to_string()).into()],
+				background: None,
+				params: SlideParams {
+					transition: Some(TransitionKind::Zoom),
+					transition_duration: Some(Duration::from_millis(1500)),
+					footer: Some(false),
+					..Default::default()
+				},
+			},
+		];
+
+		let (_, (slides, _)) = parse_slides(
+			r#"first
+
+---{ transition: zoom; transition_duration: 1.5s; footer: false; }
+second
+
+"#,
+		)?;
+
+		assert_eq!(expected, slides);
+		Ok(())
+	}
+
+	#[test]
+	fn parse_transition_kind() -> Result<()> {
+		let (_, (params, warnings)) = super::parse_slide_params("{ transition: slide-left; }")?;
+		assert_eq!(params.transition, Some(TransitionKind::SlideLeft));
+		assert!(warnings.is_empty());
+
+		let (_, (params, _)) = super::parse_slide_params("{ transition: slide_left; }")?;
+		assert_eq!(params.transition, Some(TransitionKind::SlideLeft));
+		Ok(())
+	}
+
+	#[test]
+	fn parse_alignment() -> Result<()> {
+		let expected = Slide {
+			nodes: vec![
+				SlideNode::Header(HeaderSize::Two, "Agenda".to_string()).into(),
+				Node {
+					kind: SlideNode::UnnumberedList(vec!["one".to_string(), "two".to_string()]),
+					params: NodeParams {
+						align: Some(HorizontalAlign::Left),
+						..Default::default()
+					},
+				},
+			],
+			background: None,
+			params: SlideParams {
+				align: Some(HorizontalAlign::Right),
+				valign: Some(VerticalAlign::Top),
+				..Default::default()
+			},
+		};
+
+		let (_, (slide, _)) = parse_slide(
+			r#"---{ align: right; valign: top; }
+## Agenda
+
+{ align: left; }
+- one
+- two
+
+"#,
+		)?;
+
+		assert_eq!(expected, slide);
+		Ok(())
+	}
+
+	#[test]
+	fn parse_node_attributes() -> Result<()> {
+		let expected = Slide {
+			nodes: vec![
+				Node {
+					kind: SlideNode::Text("Hello".to_string()),
+					params: NodeParams {
+						size: Some(60),
+						color: Some(Rgb(0xff, 0x88, 0x00)),
+						fragment: true,
+						id: Some("greeting".to_string()),
+						..Default::default()
+					},
+				},
+				Node {
+					kind: SlideNode::CodeBlock(
+						Language::Rust,
+						CodeBlockParams {
+							font_size: Some(20),
+							font_style: Some(CodeFontStyle::Bold),
+							block_terminator: None,
+						},
+						"fn main() {}\n".to_string(),
+					),
+					params: NodeParams {
+						align: Some(HorizontalAlign::Left),
+						..Default::default()
+					},
+				},
+			],
+			..Default::default()
+		};
+
+		let (_, (slide, _)) = parse_slide(
+			r#"{ size: 60; color: #f80; fragment: true; id: greeting; unknown: 1 }
+Hello
+
+{ align: left; font_size: 20 }
+| font_style: bold
+```rust
+fn main() {}
+```
+
+"#,
+		)?;
+
+		assert_eq!(expected, slide);
+		Ok(())
+	}
+
+	#[test]
+	fn parse_front_matter_test() -> Result<()> {
+		let expected = FrontMatter {
+			transition: Some(TransitionKind::Fade),
+			transition_duration: Some(Duration::from_millis(300)),
+			footer: true,
+			author: Some("Maciej Sołtys".to_string()),
+			event: Some("Rust Meetup".to_string()),
+			theme: Theme::Light,
+			fallback_fonts: vec![
+				"fonts/NotoColorEmoji.ttf".to_string(),
+				"fonts/NotoSansCJK.otf".to_string(),
+			],
+			..Default::default()
+		};
+
+		let (tail, (front_matter, _)) = parse_front_matter(
+			r#"+++
+transition: fade
+transition_duration: 300ms
+footer: true
+author: Maciej Sołtys
+event: Rust Meetup
+theme: light
+fallback_fonts: fonts/NotoColorEmoji.ttf, fonts/NotoSansCJK.otf
++++
+# Hello
+"#,
+		)?;
+
+		assert_eq!(expected, front_matter);
+		assert_eq!(
+			front_matter.footer_text(),
+			"Maciej Sołtys \u{00b7} Rust Meetup"
+		);
+		assert_eq!(tail, "# Hello\n");
+
+		// Saved on Windows
+		let (tail, (front_matter, _)) =
+			parse_front_matter("+++\r\nfooter: true\r\ntheme: light\r\n+++\r\n# Hello\r\n")?;
+		assert!(front_matter.footer);
+		assert_eq!(front_matter.theme, Theme::Light);
+		assert_eq!(tail, "# Hello\r\n");
+
+		let (tail, (front_matter, _)) = parse_front_matter("# Hello\n")?;
+		assert_eq!(FrontMatter::default(), front_matter);
+		assert_eq!(tail, "# Hello\n");
+		Ok(())
+	}
+
+	#[test]
+	fn parse_warnings() -> Result<()> {
+		let presentation = parse_presentation(
+			String::from("warnings"),
+			PathBuf::from("warnings.md"),
+			r#"+++
+theme: solarized
++++
+{ align: middle; sparkles: true }
+# Hello
+"#,
+		)?;
+		assert_eq!(
+			presentation.warnings,
+			vec![
+				"Invalid value \"solarized\" of \"theme\"".to_string(),
+				"Invalid value \"middle\" of \"align\"".to_string(),
+				"Unknown attribute \"sparkles\"".to_string(),
+			]
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn parse_warnings_once() -> Result<()> {
+		let presentation = parse_presentation(
+			String::from("warnings"),
+			PathBuf::from("warnings.md"),
+			r#"# First
+
+---{ sparkles: true; }
+| not_code: true
+Just text
+
+"#,
+		)?;
+		// The divider is parsed by both slides, the pipe line is tried as code block params
+		assert_eq!(
+			presentation.warnings,
+			vec!["Unknown slide param \"sparkles\"".to_string()]
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn figure_numbers() -> Result<()> {
+		let presentation = parse_presentation(
+			String::from("figures"),
+			PathBuf::from("figures.md"),
+			r#"+++
+captions: true
+figure_numbers: true
++++
+![Ferris](ferris.png)
+
+![](no-alt-text.png)
+
+---
+
+![Not a figure](ferris.png){ caption: false; }
+
+![Ferris again](ferris.png)
+
+---
+
+# The end
+"#,
+		)?;
+
+		assert!(presentation.front_matter.captions);
+		assert_eq!(presentation.first_figure_number(0), Some(1));
+		assert_eq!(presentation.first_figure_number(1), Some(2));
+		assert_eq!(presentation.first_figure_number(2), Some(3));
+		Ok(())
+	}
+
 	#[test]
 	fn parse_slide_test() -> anyhow::Result<()> {
 		let (tail, slide) = parse_slide("hello\n\nworld")?;
@@ -0,0 +1,348 @@
+use log::*;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Mutex, OnceLock};
+
+/// How many presentations are remembered
+const MAX_RECENT: usize = 10;
+/// How many presentations the last slide is remembered of
+const MAX_LAST_SLIDES: usize = 100;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecentPresentation {
+	pub path: PathBuf,
+	pub title: String,
+	pub slide_count: usize,
+}
+
+/// Recently opened presentations, most recent first. Kept in `recent` in the data directory as
+/// `slide count<TAB>title<TAB>path` lines.
+#[derive(Debug, Clone, Default)]
+pub struct RecentPresentations {
+	pub entries: Vec<RecentPresentation>,
+}
+
+impl RecentPresentations {
+	/// Presentations that still exist
+	pub fn load() -> Self {
+		let mut recent = match read_data_file("recent") {
+			Some(data) => RecentPresentations::parse(&data),
+			None => Default::default(),
+		};
+		recent.entries.retain(|entry| entry.path.is_file());
+		recent
+	}
+
+	pub fn parse(data: &str) -> Self {
+		let entries =
+			data.lines()
+				.filter(|line| !line.trim().is_empty())
+				.filter_map(|line| {
+					let mut fields = line.splitn(3, '\t');
+					let entry = match (fields.next(), fields.next(), fields.next()) {
+						(Some(slide_count), Some(title), Some(path)) => slide_count
+							.parse()
+							.ok()
+							.map(|slide_count| RecentPresentation {
+								path: PathBuf::from(path),
+								title: title.to_string(),
+								slide_count,
+							}),
+						_ => None,
+					};
+					if entry.is_none() {
+						warn!("Invalid recent presentation \"{}\"", line);
+					}
+					entry
+				})
+				.take(MAX_RECENT)
+				.collect();
+		RecentPresentations { entries }
+	}
+
+	pub fn save(&self) {
+		write_data_file("recent", &self.to_string());
+	}
+
+	/// Moves the presentation to the top, returns whether anything changed
+	pub fn add(&mut self, mut entry: RecentPresentation) -> bool {
+		// Tabs and line breaks would break the file format
+		entry.title = entry.title.split_whitespace().collect::<Vec<_>>().join(" ");
+		if self.entries.first() == Some(&entry) {
+			return false;
+		}
+		self.entries.retain(|recent| recent.path != entry.path);
+		self.entries.insert(0, entry);
+		self.entries.truncate(MAX_RECENT);
+		true
+	}
+
+	/// Forgets the presentation at `idx`, returns whether there was one
+	pub fn remove(&mut self, idx: usize) -> bool {
+		if idx >= self.entries.len() {
+			return false;
+		}
+		self.entries.remove(idx);
+		true
+	}
+}
+
+impl std::fmt::Display for RecentPresentations {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		for entry in &self.entries {
+			writeln!(
+				f,
+				"{}\t{}\t{}",
+				entry.slide_count,
+				entry.title,
+				entry.path.display()
+			)?;
+		}
+		Ok(())
+	}
+}
+
+/// Slide each presentation was left at, by canonical path, most recently left first. Kept in
+/// `last_slides` in the data directory as `slide index<TAB>path` lines.
+#[derive(Debug, Clone, Default)]
+pub struct LastSlides {
+	slides: Vec<(PathBuf, usize)>,
+}
+
+impl LastSlides {
+	/// Slides of presentations that still exist
+	pub fn load() -> Self {
+		let mut last_slides = match read_data_file("last_slides") {
+			Some(data) => LastSlides::parse(&data),
+			None => Default::default(),
+		};
+		last_slides.slides.retain(|(path, _)| path.is_file());
+		last_slides
+	}
+
+	pub fn parse(data: &str) -> Self {
+		let slides = data
+			.lines()
+			.filter(|line| !line.trim().is_empty())
+			.filter_map(|line| {
+				let slide = line
+					.split_once('\t')
+					.and_then(|(idx, path)| Some((PathBuf::from(path), idx.parse().ok()?)));
+				if slide.is_none() {
+					warn!("Invalid last slide \"{}\"", line);
+				}
+				slide
+			})
+			.take(MAX_LAST_SLIDES)
+			.collect();
+		LastSlides { slides }
+	}
+
+	/// Writes the file on a background thread, it's saved whenever the slide changes
+	pub fn save(&self) {
+		write_data_file_in_background("last_slides", self.to_string());
+	}
+
+	pub fn get(&self, path: &Path) -> Option<usize> {
+		self.slides
+			.iter()
+			.find(|(slide_path, _)| slide_path == path)
+			.map(|(_, slide_idx)| *slide_idx)
+	}
+
+	/// Moves the presentation to the top, returns whether the slide changed
+	pub fn set(&mut self, path: PathBuf, slide_idx: usize) -> bool {
+		let previous = self.get(&path);
+		self.slides.retain(|(slide_path, _)| *slide_path != path);
+		self.slides.insert(0, (path, slide_idx));
+		self.slides.truncate(MAX_LAST_SLIDES);
+		previous != Some(slide_idx)
+	}
+}
+
+impl std::fmt::Display for LastSlides {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		for (path, slide_idx) in &self.slides {
+			writeln!(f, "{}\t{}", slide_idx, path.display())?;
+		}
+		Ok(())
+	}
+}
+
+fn read_data_file(name: &str) -> Option<String> {
+	let path = crate::config::data_dir()?.join(name);
+	match std::fs::read_to_string(&path) {
+		Ok(data) => Some(data),
+		Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+		Err(e) => {
+			error!("Failed to read {}: {:?}", path.display(), e);
+			None
+		}
+	}
+}
+
+fn write_data_file(name: &str, data: &str) {
+	if let Some(dir) = crate::config::data_dir() {
+		write_file(&dir.join(name), data);
+	}
+}
+
+/// Replaces the file at once, so that quitting while it's written doesn't leave half of it
+fn write_file(path: &Path, data: &str) {
+	let temp_path = path.with_extension("tmp");
+	let result = path
+		.parent()
+		.map_or(Ok(()), std::fs::create_dir_all)
+		.and_then(|_| std::fs::write(&temp_path, data))
+		.and_then(|_| std::fs::rename(&temp_path, path));
+	if let Err(e) = result {
+		error!("Failed to save {}: {:?}", path.display(), e);
+	}
+}
+
+enum Write {
+	File(PathBuf, String),
+	/// Answered once the files queued before are written
+	Flush(mpsc::Sender<()>),
+}
+
+/// Files are written one after another on a single thread, so the last save wins
+fn writer() -> &'static Mutex<mpsc::Sender<Write>> {
+	static WRITER: OnceLock<Mutex<mpsc::Sender<Write>>> = OnceLock::new();
+
+	WRITER.get_or_init(|| {
+		let (sender, receiver) = mpsc::channel::<Write>();
+		std::thread::spawn(move || {
+			for write in receiver {
+				match write {
+					Write::File(path, data) => write_file(&path, &data),
+					Write::Flush(done) => {
+						let _ = done.send(());
+					}
+				}
+			}
+		});
+		Mutex::new(sender)
+	})
+}
+
+fn write_data_file_in_background(name: &str, data: String) {
+	if let Some(dir) = crate::config::data_dir() {
+		write_file_in_background(dir.join(name), data);
+	}
+}
+
+fn write_file_in_background(path: PathBuf, data: String) {
+	if let Err(mpsc::SendError(Write::File(path, _))) =
+		writer().lock().unwrap().send(Write::File(path, data))
+	{
+		error!(
+			"Failed to save {}, its writer thread is gone",
+			path.display()
+		);
+	}
+}
+
+/// Waits until the files saved in the background are written, the process can exit right after
+pub fn flush_data_files() {
+	let (done, flushed) = mpsc::channel();
+	if writer().lock().unwrap().send(Write::Flush(done)).is_ok() {
+		let _ = flushed.recv();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn entry(path: &str, title: &str, slide_count: usize) -> RecentPresentation {
+		RecentPresentation {
+			path: PathBuf::from(path),
+			title: title.to_string(),
+			slide_count,
+		}
+	}
+
+	#[test]
+	fn parse_recent() {
+		let recent = RecentPresentations::parse(
+			"12\tIntro to Rust\t/home/user/talks/rust.md\nbroken line\n3\tTabs\tin\tpath.md\n",
+		);
+		assert_eq!(
+			recent.entries,
+			vec![
+				entry("/home/user/talks/rust.md", "Intro to Rust", 12),
+				entry("in\tpath.md", "Tabs", 3),
+			]
+		);
+		assert_eq!(
+			RecentPresentations::parse(&recent.to_string()).entries,
+			recent.entries
+		);
+	}
+
+	#[test]
+	fn add_recent() {
+		let mut recent = RecentPresentations::default();
+		assert!(recent.add(entry("a.md", "A", 1)));
+		assert!(recent.add(entry("b.md", "B\ttitle", 2)));
+		assert!(!recent.add(entry("b.md", "B title", 2)));
+		assert!(recent.add(entry("a.md", "A", 5)));
+		assert_eq!(
+			recent.entries,
+			vec![entry("a.md", "A", 5), entry("b.md", "B title", 2)]
+		);
+
+		for idx in 0..MAX_RECENT {
+			recent.add(entry(&format!("{}.md", idx), "", 1));
+		}
+		assert_eq!(recent.entries.len(), MAX_RECENT);
+		assert_eq!(recent.entries[0].path, PathBuf::from("9.md"));
+	}
+
+	#[test]
+	fn remove_recent() {
+		let mut recent = RecentPresentations::default();
+		recent.add(entry("a.md", "A", 1));
+		recent.add(entry("b.md", "B", 2));
+
+		assert!(!recent.remove(2));
+		assert!(recent.remove(0));
+		assert_eq!(recent.entries, vec![entry("a.md", "A", 1)]);
+	}
+
+	#[test]
+	fn last_slides() {
+		let mut last_slides = LastSlides::parse("4\t/talks/rust.md\nnot a number\t/a.md\n");
+		assert_eq!(last_slides.get(Path::new("/talks/rust.md")), Some(4));
+		assert_eq!(last_slides.get(Path::new("/a.md")), None);
+
+		assert!(!last_slides.set(PathBuf::from("/talks/rust.md"), 4));
+		assert!(last_slides.set(PathBuf::from("/talks/rust.md"), 7));
+		assert!(last_slides.set(PathBuf::from("/b.md"), 0));
+		let parsed = LastSlides::parse(&last_slides.to_string());
+		assert_eq!(parsed.slides, last_slides.slides);
+
+		for idx in 0..MAX_LAST_SLIDES {
+			last_slides.set(PathBuf::from(format!("/{}.md", idx)), idx);
+		}
+		assert_eq!(last_slides.slides.len(), MAX_LAST_SLIDES);
+		// The least recently left ones are forgotten
+		assert_eq!(last_slides.get(Path::new("/talks/rust.md")), None);
+		assert_eq!(last_slides.get(Path::new("/0.md")), Some(0));
+	}
+
+	#[test]
+	fn last_slides_flushed() {
+		let path = std::env::temp_dir().join(format!("last_slides-{}", std::process::id()));
+		let mut last_slides = LastSlides::default();
+		last_slides.set(PathBuf::from("/talks/rust.md"), 3);
+		write_file_in_background(path.clone(), last_slides.to_string());
+		last_slides.set(PathBuf::from("/talks/rust.md"), 7);
+		write_file_in_background(path.clone(), last_slides.to_string());
+
+		flush_data_files();
+		let saved = LastSlides::parse(&std::fs::read_to_string(&path).unwrap());
+		assert_eq!(saved.get(Path::new("/talks/rust.md")), Some(7));
+		std::fs::remove_file(&path).unwrap();
+	}
+}
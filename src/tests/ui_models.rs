@@ -21,3 +21,291 @@ mod header_size {
 		assert_eq!(HeaderSize::Five.to_font_size(), 50);
 	}
 }
+
+mod animation {
+	use crate::{fragment_elapsed, Animation, Playback};
+	use iced::image::Handle;
+	use std::time::Duration;
+
+	fn frame(id: u8, delay_ms: u64) -> (Handle, Duration) {
+		(
+			Handle::from_pixels(1, 1, vec![id, id, id, 255]),
+			Duration::from_millis(delay_ms),
+		)
+	}
+
+	#[test]
+	fn frame_at_test() {
+		let frames = vec![frame(0, 100), frame(1, 50), frame(2, 100)];
+		let ids = frames
+			.iter()
+			.map(|(handle, _)| handle.id())
+			.collect::<Vec<_>>();
+		let animation = Animation::new(frames);
+		let frame_id = |elapsed_ms, playback| {
+			animation
+				.frame_at(Duration::from_millis(elapsed_ms), playback)
+				.id()
+		};
+
+		assert_eq!(frame_id(0, Playback::Loop), ids[0]);
+		assert_eq!(frame_id(120, Playback::Loop), ids[1]);
+		assert_eq!(frame_id(150, Playback::Loop), ids[2]);
+		assert_eq!(frame_id(260, Playback::Loop), ids[0]);
+		assert_eq!(frame_id(260, Playback::Once), ids[2]);
+	}
+
+	#[test]
+	fn fragment_elapsed_test() {
+		let ms = Duration::from_millis;
+		let revealed_at = [ms(0), ms(300)];
+
+		assert_eq!(fragment_elapsed(ms(500), &revealed_at, 0), ms(500));
+		assert_eq!(fragment_elapsed(ms(500), &revealed_at, 1), ms(500));
+		assert_eq!(fragment_elapsed(ms(500), &revealed_at, 2), ms(200));
+		// Not revealed yet
+		assert_eq!(fragment_elapsed(ms(500), &revealed_at, 3), Duration::ZERO);
+	}
+}
+
+mod navigation {
+	use crate::{Overview, Presentation, PresentationState, Slide, Welcome, OVERVIEW_COLUMNS};
+	use std::path::PathBuf;
+
+	fn presentation(slide_count: usize) -> Presentation {
+		Presentation {
+			title: "test".to_string(),
+			path: PathBuf::from("."),
+			source: None,
+			canonical_source: None,
+			front_matter: Default::default(),
+			fonts: Default::default(),
+			slides: (0..slide_count).map(|_| Slide::default()).collect(),
+			warnings: Vec::new(),
+		}
+	}
+
+	#[test]
+	fn jump_back_test() {
+		let presentation = presentation(10);
+		let mut state = PresentationState::default();
+
+		state.next(&presentation);
+		state.jump_to(usize::MAX, &presentation);
+		assert_eq!(state.slide_idx, 9);
+
+		state.jump_back(&presentation);
+		assert_eq!(state.slide_idx, 1);
+		state.jump_back(&presentation);
+		assert_eq!(state.slide_idx, 9);
+	}
+
+	#[test]
+	fn jump_to_typed_test() {
+		let presentation = presentation(20);
+		let mut state = PresentationState::default();
+
+		state.type_digit(1);
+		state.type_digit(2);
+		state.jump_to_typed(&presentation);
+		assert_eq!(state.slide_idx, 11);
+		assert_eq!(state.typed_slide_number, None);
+
+		state.type_digit(9);
+		state.type_digit(9);
+		state.jump_to_typed(&presentation);
+		assert_eq!(state.slide_idx, 19);
+	}
+
+	#[test]
+	fn overview_selection_test() {
+		let columns = OVERVIEW_COLUMNS as isize;
+		let mut overview = Overview::new(2);
+		assert!(overview.scroll_to_selected);
+
+		overview.move_selection(-columns, 10);
+		assert_eq!(overview.selected, 0);
+		overview.move_selection(columns * 2, 10);
+		assert_eq!(overview.selected, 8);
+		overview.move_selection(columns, 10);
+		assert_eq!(overview.selected, 9);
+		overview.move_selection(1, 0);
+		assert_eq!(overview.selected, 0);
+	}
+
+	#[test]
+	fn welcome_selection_test() {
+		// Two recent presentations and the example
+		let entry_count = 3;
+		let mut welcome = Welcome::default();
+
+		welcome.move_selection(-1, entry_count);
+		assert_eq!(welcome.selected, 0);
+		assert!(welcome.scroll_to_selected);
+		welcome.move_selection(1, entry_count);
+		welcome.move_selection(1, entry_count);
+		welcome.move_selection(1, entry_count);
+		assert_eq!(welcome.selected, 2);
+		// The selected entry was removed
+		welcome.move_selection(0, entry_count - 1);
+		assert_eq!(welcome.selected, 1);
+	}
+}
+
+mod wheel_navigation {
+	use crate::config::Action;
+	use crate::WheelNavigation;
+	use iced::mouse::ScrollDelta;
+	use std::time::{Duration, Instant};
+
+	#[test]
+	fn scroll_test() {
+		let mut wheel = WheelNavigation::default();
+		let start = Instant::now();
+		let pixels = |y| ScrollDelta::Pixels { x: 0.0, y };
+
+		assert_eq!(wheel.scroll(pixels(-30.0), start), None);
+		assert_eq!(wheel.scroll(pixels(-30.0), start), Some(Action::Next));
+		// Momentum right after the slide change is ignored
+		let momentum = start + Duration::from_millis(100);
+		assert_eq!(wheel.scroll(pixels(-200.0), momentum), None);
+
+		let later = start + Duration::from_secs(1);
+		assert_eq!(
+			wheel.scroll(ScrollDelta::Lines { x: 0.0, y: 1.0 }, later),
+			Some(Action::Previous)
+		);
+	}
+}
+
+mod reanchor {
+	use crate::parser::parse_presentation;
+	use crate::{Presentation, PresentationState};
+	use std::path::PathBuf;
+
+	fn presentation(input: &str) -> Presentation {
+		parse_presentation("test".to_string(), PathBuf::from("."), input).unwrap()
+	}
+
+	fn reanchored(old: &str, new: &str, slide_idx: usize) -> usize {
+		let (old, new) = (presentation(old), presentation(new));
+		let mut state = PresentationState {
+			slide_idx,
+			..Default::default()
+		};
+		state.reanchor(&old, &new);
+		state.slide_idx
+	}
+
+	#[test]
+	fn reanchor_test() {
+		let old = "# One\n\n---\n\n# Two\n\n---\n\n# Three\n\n";
+
+		// Slide inserted before the current one
+		let new = "# Zero\n\n---\n\n# One\n\n---\n\n# Two\n\n---\n\n# Three\n\n";
+		assert_eq!(reanchored(old, new, 1), 2);
+
+		// Header edited, explicit id kept
+		let old_with_id = "# One\n\n---{ id: two; }\n# Two\n\n---\n\n# Three\n\n";
+		let new_with_id = "# Intro\n\n---\n\n# One\n\n---{ id: two; }\n# Second\n\n";
+		assert_eq!(reanchored(old_with_id, new_with_id, 1), 2);
+
+		// Text edited slightly
+		let old_text = "first slide\n\n---\n\nsome words on the second slide\n\n";
+		let new_text =
+			"new slide\n\n---\n\nfirst slide\n\n---\n\nsome words on the second slide!\n\n";
+		assert_eq!(reanchored(old_text, new_text, 1), 2);
+
+		// Current slide deleted at the end of the deck
+		assert_eq!(reanchored(old, "# One\n\n", 2), 0);
+	}
+}
+
+mod letterbox {
+	use crate::letterbox;
+	use iced::Size;
+
+	#[test]
+	fn letterbox_test() {
+		// Bars on the sides of a wide window
+		assert_eq!(
+			letterbox(Size::new(2000.0, 900.0), 16.0 / 9.0),
+			Size::new(1600.0, 900.0)
+		);
+		// Bars above and below a tall window
+		assert_eq!(
+			letterbox(Size::new(800.0, 800.0), 4.0 / 3.0),
+			Size::new(800.0, 600.0)
+		);
+	}
+}
+
+mod export {
+	use crate::commands::{export, load, outline, ImageCache};
+	use crate::parser::parse_presentation;
+	use iced::Size;
+	use iced_native::image::Data as ImageData;
+	use std::path::PathBuf;
+
+	#[test]
+	fn outline_test() {
+		let presentation = parse_presentation(
+			"test".to_string(),
+			PathBuf::from("."),
+			"# Title\n\nSome text\n\n---\n\n- one\n- two\n\n![Ferris](ferris.png)\n\n",
+		)
+		.unwrap();
+		assert_eq!(
+			outline(&presentation),
+			"# Title\n\nSome text\n\n---\n\n- one\n- two\n\n![Ferris](ferris.png)\n\n"
+		);
+	}
+
+	#[test]
+	fn export_original_image_test() -> anyhow::Result<()> {
+		let dir = std::env::temp_dir().join(format!("export-test-{}", std::process::id()));
+		std::fs::create_dir_all(&dir)?;
+		let deck = dir.join("deck.md");
+		let exported = dir.join("exported.md");
+		::image::RgbaImage::new(400, 200).save(dir.join("big.png"))?;
+		std::fs::write(&deck, "![Big](big.png)\n\n")?;
+
+		// Shown in a small window, the decoded pixels are downscaled
+		let runtime = tokio::runtime::Runtime::new()?;
+		let presentation = runtime.block_on(load(
+			Some(deck.clone()),
+			ImageCache::default(),
+			Size::new(100.0, 50.0),
+		))?;
+		let (_, image) = presentation.images().next().unwrap();
+		assert_eq!(image.source_size, Some(Size::new(400.0, 200.0)));
+		match image.handle.as_ref().map(|handle| handle.data()) {
+			Some(ImageData::Pixels { width, .. }) => assert!(*width <= 100),
+			data => panic!("unexpected image data {:?}", data),
+		}
+
+		export(&deck, Some(&exported))?;
+		assert_eq!(std::fs::read_to_string(&exported)?, "![Big](big.png)\n\n");
+		assert_eq!(::image::image_dimensions(dir.join("big.png"))?, (400, 200));
+
+		std::fs::remove_dir_all(&dir)?;
+		Ok(())
+	}
+}
+
+mod glyph_coverage {
+	use crate::views::fonts::{DEJAVU_SANS, LATO_REGULAR};
+	use crate::{font_name, GlyphCoverage};
+
+	#[test]
+	fn font_runs_test() {
+		let runs = GlyphCoverage::embedded()
+			.font_runs("A → B 日", LATO_REGULAR)
+			.into_iter()
+			.map(|(face, run)| (font_name(face.font()), run))
+			.collect::<Vec<_>>();
+		let (lato, dejavu) = (font_name(LATO_REGULAR), font_name(DEJAVU_SANS));
+		// Characters no font has stay with the primary one
+		assert_eq!(runs, vec![(lato, "A "), (dejavu, "→"), (lato, " B 日")]);
+	}
+}
@@ -1,182 +1,994 @@
+use crate::config::{Action, KeyBindings};
+use crate::recent::RecentPresentations;
 use crate::{
-	App, CodeBlockParams, CodeFontStyle, HeaderSize, Image, Language, Presentation,
-	PresentationState, Slide, SlideNode,
+	font_name, fragment_elapsed, App, Blank, CodeBlockParams, CodeFontStyle, FontFace, Fonts,
+	HeaderSize, HorizontalAlign, Image, ImageFloat, ImageParams, ImageSize, Language, Message,
+	Overview, Presentation, PresentationState, Slide, SlideNode, Theme, TransitionKind,
+	VectorImage, VerticalAlign, Welcome, OVERVIEW_COLUMNS,
 };
 use iced::*;
 use iced_native::image::Data as ImageData;
 
 type Element = iced::Element<'static, <App as Application>::Message>;
 
-pub fn welcome_screen() -> Element {
+/// Around the slide nodes
+const SLIDE_PADDING: u16 = 20;
+
+const WELCOME_LIST_WIDTH: u16 = 800;
+/// Heights the welcome screen is laid out with, to scroll the selected entry into view
+const WELCOME_PADDING: u16 = 40;
+const WELCOME_SPACING: u16 = 20;
+const WELCOME_TITLE_HEIGHT: u16 = 120;
+const WELCOME_PROMPT_HEIGHT: u16 = 30;
+const WELCOME_HEADING_HEIGHT: u16 = 26;
+const WELCOME_LIST_SPACING: u16 = 10;
+const WELCOME_ENTRY_PADDING: u16 = 12;
+const WELCOME_ENTRY_TITLE_HEIGHT: u16 = 34;
+const WELCOME_ENTRY_DETAILS_HEIGHT: u16 = 22;
+const WELCOME_ENTRY_HEIGHT: u16 =
+	2 * WELCOME_ENTRY_PADDING + WELCOME_ENTRY_TITLE_HEIGHT + 4 + WELCOME_ENTRY_DETAILS_HEIGHT;
+
+/// Recent presentations followed by the example, each can be clicked or selected with the keyboard
+pub fn welcome_screen<'a>(
+	recent: &RecentPresentations,
+	welcome: &'a mut Welcome,
+	key_bindings: &KeyBindings,
+	viewport: Size,
+) -> iced::Element<'a, Message> {
 	let welcome_msg_txt = Text::new("Presentation")
 		.width(Length::Fill)
+		.height(Length::Units(WELCOME_TITLE_HEIGHT))
 		.size(100)
-		.color([1.0, 1.0, 1.0])
+		.color(WHITE)
 		.horizontal_alignment(HorizontalAlignment::Center)
 		.vertical_alignment(VerticalAlignment::Center);
+	let prompt = match key_bindings.key_name(Action::Open) {
+		Some(key) => format!(
+			"Drop a presentation onto the window or press {} to open one",
+			key
+		),
+		None => String::from("Drop a presentation onto the window to open it"),
+	};
+	let prompt = Text::new(prompt)
+		.width(Length::Fill)
+		.height(Length::Units(WELCOME_PROMPT_HEIGHT))
+		.size(24)
+		.color(FOOTER_GRAY)
+		.horizontal_alignment(HorizontalAlignment::Center);
 
-	Column::new()
-		.spacing(20)
+	let example = (
+		String::from("Example presentation"),
+		String::from("Built into the app"),
+	);
+	let entries = recent
+		.entries
+		.iter()
+		.map(|entry| {
+			let slides = match entry.slide_count {
+				1 => String::from("1 slide"),
+				n => format!("{} slides", n),
+			};
+			let details = format!("{} · {}", slides, entry.path.display());
+			(entry.title.clone(), details)
+		})
+		.chain(std::iter::once(example));
+
+	let selected = welcome.selected;
+	let entry_count = recent.entries.len() + 1;
+	welcome.buttons.resize_with(entry_count, Default::default);
+	welcome
+		.remove_buttons
+		.resize_with(recent.entries.len(), Default::default);
+
+	let heading_height = if recent.entries.is_empty() {
+		0.0
+	} else {
+		f32::from(WELCOME_HEADING_HEIGHT + WELCOME_LIST_SPACING)
+	};
+	let list_top = f32::from(
+		WELCOME_PADDING
+			+ WELCOME_TITLE_HEIGHT
+			+ WELCOME_SPACING
+			+ WELCOME_PROMPT_HEIGHT
+			+ WELCOME_SPACING,
+	) + heading_height;
+	let entry_top =
+		|idx: usize| list_top + idx as f32 * f32::from(WELCOME_ENTRY_HEIGHT + WELCOME_LIST_SPACING);
+	if std::mem::take(&mut welcome.scroll_to_selected) {
+		let spacing = f32::from(WELCOME_LIST_SPACING);
+		scroll_into_view(
+			&mut welcome.scroll,
+			viewport,
+			entry_top(entry_count) - spacing + f32::from(WELCOME_PADDING),
+			entry_top(selected) - spacing,
+			entry_top(selected) + f32::from(WELCOME_ENTRY_HEIGHT) + spacing,
+		);
+	}
+
+	let mut list = Column::new()
+		.spacing(WELCOME_LIST_SPACING)
+		.width(Length::Units(WELCOME_LIST_WIDTH));
+	if !recent.entries.is_empty() {
+		list = list.push(
+			Text::new("Recent presentations")
+				.height(Length::Units(WELCOME_HEADING_HEIGHT))
+				.size(20)
+				.color(FOOTER_GRAY),
+		);
+	}
+	let mut remove_buttons = welcome.remove_buttons.iter_mut();
+	for (idx, ((title, details), button_state)) in
+		entries.zip(welcome.buttons.iter_mut()).enumerate()
+	{
+		let content = Column::new()
+			.spacing(4)
+			.push(
+				Text::new(title)
+					.height(Length::Units(WELCOME_ENTRY_TITLE_HEIGHT))
+					.size(28)
+					.color(WHITE),
+			)
+			.push(
+				Text::new(details)
+					.height(Length::Units(WELCOME_ENTRY_DETAILS_HEIGHT))
+					.size(18)
+					.color(FOOTER_GRAY),
+			);
+		let button = Button::new(button_state, content)
+			.width(Length::Fill)
+			.height(Length::Units(WELCOME_ENTRY_HEIGHT))
+			.padding(WELCOME_ENTRY_PADDING)
+			.on_press(Message::WelcomeSelected(idx))
+			.style(styles::WelcomeEntry {
+				selected: idx == selected,
+			});
+		let mut row = Row::new()
+			.spacing(10)
+			.align_items(Align::Center)
+			.push(button);
+		if let Some(remove_state) = remove_buttons.next() {
+			let remove = Button::new(
+				remove_state,
+				Text::new("Remove").size(18).color(FOOTER_GRAY),
+			)
+			.padding(WELCOME_ENTRY_PADDING)
+			.on_press(Message::WelcomeRemoved(idx))
+			.style(styles::WelcomeEntry { selected: false });
+			row = row.push(remove);
+		}
+		list = list.push(row);
+	}
+
+	let content = Column::new()
+		.spacing(WELCOME_SPACING)
+		.padding(WELCOME_PADDING)
 		.align_items(Align::Center)
 		.push(welcome_msg_txt)
+		.push(prompt)
+		.push(list);
+
+	Scrollable::new(&mut welcome.scroll)
+		.width(Length::Fill)
+		.height(Length::Fill)
+		.push(content)
+		.into()
+}
+
+pub fn blank(blank: Blank) -> Element {
+	Container::new(Space::new(Length::Fill, Length::Fill))
+		.width(Length::Fill)
+		.height(Length::Fill)
+		.style(styles::Blank(blank))
 		.into()
 }
 
 static DEFAULT_SLIDE: Slide = Slide {
 	nodes: Vec::new(),
 	background: None,
+	params: crate::SlideParams {
+		transition: None,
+		transition_duration: None,
+		footer: None,
+		align: None,
+		valign: None,
+		id: None,
+	},
 };
 
-pub fn presentation(presentation: &Presentation, state: &PresentationState) -> Element {
-	let slide = match presentation.slides.get(state.slide_idx) {
+/// How the slide is drawn: fonts, alignment and the current frame of a transition
+#[derive(Debug, Copy, Clone)]
+struct Appearance {
+	opacity: f32,
+	scale: f32,
+	/// Horizontal offset as a fraction of the slide width
+	offset: f32,
+	fonts: Fonts,
+	align: HorizontalAlign,
+	theme: Theme,
+	color: Color,
+	/// Overrides the default font size of the node
+	size: Option<u16>,
+	/// Size of the slide content, percent image sizes are relative to it
+	slide_area: Size,
+	/// Picks the frame of animated images
+	animation_elapsed: std::time::Duration,
+}
+
+impl Default for Appearance {
+	fn default() -> Self {
+		Appearance {
+			opacity: 1.0,
+			scale: 1.0,
+			offset: 0.0,
+			fonts: Fonts::default(),
+			align: HorizontalAlign::Center,
+			theme: Theme::Dark,
+			color: WHITE.into(),
+			size: None,
+			slide_area: Size::ZERO,
+			animation_elapsed: Default::default(),
+		}
+	}
+}
+
+impl Appearance {
+	fn horizontal_alignment(&self) -> HorizontalAlignment {
+		match self.align {
+			HorizontalAlign::Left => HorizontalAlignment::Left,
+			HorizontalAlign::Center => HorizontalAlignment::Center,
+			HorizontalAlign::Right => HorizontalAlignment::Right,
+		}
+	}
+
+	fn align_x(&self) -> Align {
+		match self.align {
+			HorizontalAlign::Left => Align::Start,
+			HorizontalAlign::Center => Align::Center,
+			HorizontalAlign::Right => Align::End,
+		}
+	}
+
+	fn text_color(&self) -> Color {
+		Color {
+			a: self.color.a * self.opacity,
+			..self.color
+		}
+	}
+
+	fn font_size(&self, default_size: u16) -> u16 {
+		(f32::from(self.size.unwrap_or(default_size)) * self.scale).round() as u16
+	}
+}
+
+/// Picks the slide to draw and how to draw it, based on the running transition
+fn transition_frame(state: &PresentationState) -> (usize, Appearance) {
+	let transition_state = match state.transition {
+		Some(ref v) => v,
+		None => return (state.slide_idx, Appearance::default()),
+	};
+	// smoothstep easing
+	let t = transition_state.progress.clamp(0.0, 1.0);
+	let t = t * t * (3.0 - 2.0 * t);
+
+	match transition_state.transition.kind {
+		TransitionKind::None => (state.slide_idx, Appearance::default()),
+		TransitionKind::Fade if t < 0.5 => (
+			transition_state.from_idx,
+			Appearance {
+				opacity: 1.0 - t * 2.0,
+				..Default::default()
+			},
+		),
+		TransitionKind::Fade => (
+			state.slide_idx,
+			Appearance {
+				opacity: t * 2.0 - 1.0,
+				..Default::default()
+			},
+		),
+		TransitionKind::SlideLeft => (
+			state.slide_idx,
+			Appearance {
+				offset: 1.0 - t,
+				..Default::default()
+			},
+		),
+		TransitionKind::Zoom => (
+			state.slide_idx,
+			Appearance {
+				opacity: t,
+				scale: 0.5 + t * 0.5,
+				..Default::default()
+			},
+		),
+	}
+}
+
+pub fn presentation(
+	presentation: &Presentation,
+	state: &PresentationState,
+	key_bindings: &KeyBindings,
+	viewport: Size,
+) -> Element {
+	let (slide_idx, mut appearance) = transition_frame(state);
+	appearance.fonts = presentation.fonts;
+	appearance.theme = presentation.front_matter.theme;
+	appearance.color = appearance.theme.text_color();
+	if slide_idx == state.slide_idx {
+		appearance.animation_elapsed = state.animation_elapsed;
+	}
+
+	let slide = match presentation.slides.get(slide_idx) {
 		Some(v) => v,
 		None => {
 			log::error!(
 				"Invalid slide idx: {}. Slide count: {}",
-				state.slide_idx,
+				slide_idx,
 				presentation.slides.len()
 			);
 			&DEFAULT_SLIDE
 		}
 	};
 
-	let mut column = Column::new().spacing(10).align_items(Align::Center);
+	let show_footer = shows_footer(presentation, slide);
+	appearance.slide_area = slide_area(viewport, show_footer);
+	let (align, valign) = slide_alignment(presentation, slide);
+	appearance.align = align;
 
-	for element in &slide.nodes {
-		match element {
-			SlideNode::Header(size, txt) => {
-				column = column.push(header(*size, txt));
-			}
-			SlideNode::Text(txt) => {
-				column = column.push(text(txt));
-			}
-			SlideNode::UnnumberedList(list) => {
-				column = column.push(unnumbered_list(list));
+	let (fragments_shown, fragments_revealed_at) = if slide_idx == state.slide_idx {
+		(
+			state.fragments_revealed_at.len(),
+			state.fragments_revealed_at.as_slice(),
+		)
+	} else {
+		(usize::MAX, &[][..])
+	};
+	let content = Container::new(slide_nodes(
+		presentation,
+		slide,
+		appearance,
+		fragments_shown,
+		fragments_revealed_at,
+		presentation.first_figure_number(slide_idx),
+	))
+	.width(Length::Fill)
+	.height(Length::Fill)
+	.center_x()
+	.align_y(vertical_align(valign));
+
+	const OFFSET_PORTIONS: f32 = 1000.0;
+	let offset = (appearance.offset * OFFSET_PORTIONS) as u16;
+	let content: Element = if offset == 0 {
+		content.into()
+	} else {
+		Row::new()
+			.push(Space::with_width(Length::FillPortion(offset)))
+			.push(content.width(Length::FillPortion(OFFSET_PORTIONS as u16 - offset)))
+			.into()
+	};
+
+	let hint = match (state.typed_slide_number, state.resume_offer) {
+		(Some(number), _) => Some(format!("Go to slide {}", number)),
+		(None, Some(idx)) => Some(match key_bindings.key_name(Action::Confirm) {
+			Some(key) => format!("Press {} to resume at slide {}", key, idx + 1),
+			None => format!("Left at slide {}", idx + 1),
+		}),
+		(None, None) => None,
+	};
+	let content: Element = match hint {
+		Some(hint) => Column::new()
+			.push(content)
+			.push(
+				Text::new(hint)
+					.width(Length::Fill)
+					.size(28)
+					.color(FOOTER_GRAY)
+					.horizontal_alignment(HorizontalAlignment::Right),
+			)
+			.into(),
+		None => content,
+	};
+
+	if !show_footer {
+		return content;
+	}
+
+	Column::new()
+		.push(content)
+		.push(footer(presentation, slide_idx))
+		.into()
+}
+
+fn shows_footer(presentation: &Presentation, slide: &Slide) -> bool {
+	slide
+		.params
+		.footer
+		.unwrap_or(presentation.front_matter.footer)
+}
+
+/// Space the slide nodes are laid out in, when the slide is drawn in the `viewport`
+fn slide_area(viewport: Size, show_footer: bool) -> Size {
+	let padding = 2.0 * f32::from(SLIDE_PADDING);
+	let footer_height = if show_footer {
+		f32::from(FOOTER_HEIGHT)
+	} else {
+		0.0
+	};
+	Size::new(
+		(viewport.width - padding).max(0.0),
+		(viewport.height - padding - footer_height).max(0.0),
+	)
+}
+
+fn vertical_align(valign: VerticalAlign) -> Align {
+	match valign {
+		VerticalAlign::Top => Align::Start,
+		VerticalAlign::Center => Align::Center,
+		VerticalAlign::Bottom => Align::End,
+	}
+}
+
+fn slide_alignment(presentation: &Presentation, slide: &Slide) -> (HorizontalAlign, VerticalAlign) {
+	let front_matter = &presentation.front_matter;
+	let align = slide
+		.params
+		.align
+		.or(front_matter.align)
+		.unwrap_or(HorizontalAlign::Center);
+	let valign = slide
+		.params
+		.valign
+		.or(front_matter.valign)
+		.unwrap_or(VerticalAlign::Center);
+	(align, valign)
+}
+
+/// `slide_appearance.align` is the slide alignment, nodes may override it. Only the first
+/// `fragments_shown` fragments are drawn. Captioned images are numbered from `first_figure`.
+fn slide_nodes(
+	presentation: &Presentation,
+	slide: &Slide,
+	slide_appearance: Appearance,
+	fragments_shown: usize,
+	fragments_revealed_at: &[std::time::Duration],
+	first_figure: Option<usize>,
+) -> Element {
+	let mut column = Column::new()
+		.spacing(10)
+		.padding(SLIDE_PADDING)
+		.width(Length::Fill)
+		.align_items(Align::Center);
+
+	let mut fragment_idx = 0;
+	let mut floating = None;
+	let mut next_figure = first_figure;
+	for node in &slide.nodes {
+		// Numbered before skipping hidden fragments, so numbers don't change while revealing them
+		let figure = match node.kind {
+			SlideNode::Image(ref image) if presentation.has_caption(image) => {
+				let figure = next_figure;
+				next_figure = next_figure.map(|n| n + 1);
+				Some(figure)
 			}
-			SlideNode::NumberedList(list) => {
-				column = column.push(numbered_list(list));
+			_ => None,
+		};
+		if node.params.fragment {
+			fragment_idx += 1;
+			if fragment_idx > fragments_shown {
+				continue;
 			}
-			SlideNode::Image(Image {
-				path: _name,
-				alt_text,
-				params,
-				handle,
-			}) => {
-				let image: iced::Element<_> = match handle {
-					Some(ref handle) => {
-						let mut scaled_height = None;
-						match handle.data() {
-							ImageData::Pixels { height, .. } => {
-								if let Some(scale) = params.scale {
-									scaled_height = Some(*height as f64 * (scale as f64 / 100.0));
-									log::trace!(
-										"setting img height to {:?} from {}",
-										scaled_height,
-										*height
-									);
-								}
-							}
-							ImageData::Path(_) => {
-								log::error!("image data contains a path variant");
-							}
-							ImageData::Bytes(_) => {
-								log::error!("image data contains a bytes variant");
-							}
-						}
-						let mut image = image::Image::new(handle.clone());
-
-						if let Some(scaled_height) = scaled_height {
-							log::trace!("setting img height to {}", scaled_height);
-							image = image.height(Length::Units(scaled_height as u16));
-						}
-
-						image.into()
-					}
-					None => text(alt_text).into(),
+		}
+		let appearance = Appearance {
+			align: node.params.align.unwrap_or(slide_appearance.align),
+			color: node
+				.params
+				.color
+				.map(Color::from)
+				.unwrap_or(slide_appearance.color),
+			size: node.params.size,
+			animation_elapsed: if node.params.fragment {
+				fragment_elapsed(
+					slide_appearance.animation_elapsed,
+					fragments_revealed_at,
+					fragment_idx,
+				)
+			} else {
+				slide_appearance.animation_elapsed
+			},
+			..slide_appearance
+		};
+		let element: Element = match node.kind {
+			SlideNode::Header(size, ref txt) => header(size, txt, appearance),
+			SlideNode::Text(ref txt) => text(txt, appearance),
+			SlideNode::UnnumberedList(ref list) => unnumbered_list(list, appearance),
+			SlideNode::NumberedList(ref list) => numbered_list(list, appearance),
+			SlideNode::Image(ref img) => {
+				let element = match (&img.vector, img.handle_at(appearance.animation_elapsed)) {
+					(Some(vector), _) => vector_image(vector, &img.params, appearance),
+					(None, Some(handle)) => image(handle, img, appearance),
+					(None, None) => match img.error {
+						Some(ref error) => image_placeholder(img, error, appearance),
+						None => text(&img.alt_text, appearance),
+					},
 				};
-
-				column = column.push(image);
+				match figure {
+					Some(figure) if img.is_loaded() => Column::new()
+						.spacing(5)
+						.align_items(Align::Center)
+						.push(element)
+						.push(caption(&img.alt_text, figure, appearance))
+						.into(),
+					_ => element,
+				}
 			}
-			SlideNode::CodeBlock(lang, params, txt) => {
-				column = column.push(code_block(*lang, params, txt));
+			SlideNode::CodeBlock(lang, ref params, ref txt) => {
+				code_block(lang, params, txt, appearance)
 			}
 			SlideNode::Comment(_) => continue,
+		};
+
+		if let SlideNode::Image(Image {
+			params: ImageParams {
+				float: Some(float), ..
+			},
+			..
+		}) = node.kind
+		{
+			if floating.is_none() {
+				floating = Some((float, element));
+				continue;
+			}
 		}
+
+		let element = Container::new(element)
+			.width(Length::Fill)
+			.align_x(appearance.align_x());
+		column = match floating.take() {
+			Some((float, image)) => column.push(beside(image, element.into(), float)),
+			None => column.push(element),
+		};
+	}
+	if let Some((_, image)) = floating {
+		column = column.push(image);
+	}
+
+	column.into()
+}
+
+/// Places a floating image beside the node following it
+fn beside(image: Element, element: Element, float: ImageFloat) -> Element {
+	let row = Row::new().spacing(20).align_items(Align::Center);
+	match float {
+		ImageFloat::Left => row.push(image).push(element),
+		ImageFloat::Right => row.push(element).push(image),
 	}
+	.into()
+}
+
+/// `handle` is the current frame of `img`
+fn image(handle: &image::Handle, img: &Image, appearance: Appearance) -> Element {
+	let natural_size = match (img.source_size, handle.data()) {
+		(Some(source_size), _) => source_size,
+		(None, ImageData::Pixels { width, height, .. }) => Size::new(*width as f32, *height as f32),
+		(None, ImageData::Path(_)) => {
+			log::error!("image data contains a path variant");
+			return image::Image::new(handle.clone()).into();
+		}
+		(None, ImageData::Bytes(_)) => {
+			log::error!("image data contains a bytes variant");
+			return image::Image::new(handle.clone()).into();
+		}
+	};
+	let (width, height) = image_length(&img.params, natural_size, appearance);
+
+	image::Image::new(handle.clone())
+		.width(width)
+		.height(height)
+		.into()
+}
 
-	//TODO: Background image. Tracking issue: https://github.com/hecrj/iced/issues/450
+/// Drawn in place of images that failed to load, so they're noticed before presenting
+fn image_placeholder(img: &Image, error: &str, appearance: Appearance) -> Element {
+	let line = |txt: String, size: u16, color: Color| {
+		Text::new(txt)
+			.font(appearance.fonts.body)
+			.size(appearance.font_size(size))
+			.color(Color {
+				a: appearance.opacity,
+				..color
+			})
+			.horizontal_alignment(HorizontalAlignment::Center)
+	};
+	let mut column = Column::new()
+		.spacing(10)
+		.align_items(Align::Center)
+		.push(line(img.path.clone(), 28, appearance.theme.text_color()))
+		.push(line(error.to_string(), 22, PLACEHOLDER_RED.into()));
+	if !img.alt_text.is_empty() {
+		column = column.push(line(img.alt_text.clone(), 22, FOOTER_GRAY.into()));
+	}
 
+	// Takes the space of the image if its size is given
+	let length = |size: Option<ImageSize>, slide_size: f32| match size {
+		Some(size) => Length::Units((size.resolve(slide_size) * appearance.scale).round() as u16),
+		None => Length::Shrink,
+	};
 	Container::new(column)
-		.width(Length::Fill)
-		.height(Length::Fill)
+		.width(length(img.params.width, appearance.slide_area.width))
+		.height(length(img.params.height, appearance.slide_area.height))
+		.padding(20)
 		.center_x()
 		.center_y()
+		.style(styles::ImagePlaceholder)
 		.into()
 }
 
-static WHITE: [f32; 3] = [1.0, 1.0, 1.0];
+/// The renderer rasterizes SVGs at the size they're drawn at, so they stay crisp when resized
+fn vector_image(vector: &VectorImage, params: &ImageParams, appearance: Appearance) -> Element {
+	let (width, height) = image_length(params, vector.size, appearance);
 
-fn header(size: HeaderSize, txt: &str) -> Element {
-	Row::new()
-		.padding(20)
+	Svg::new(vector.handle.clone())
+		.width(width)
+		.height(height)
+		.into()
+}
+
+fn image_length(
+	params: &ImageParams,
+	natural_size: Size,
+	appearance: Appearance,
+) -> (Length, Length) {
+	let size = params.display_size(natural_size, appearance.slide_area);
+	log::trace!("setting img size to {:?} from {:?}", size, natural_size);
+
+	(
+		Length::Units((size.width * appearance.scale).round() as u16),
+		Length::Units((size.height * appearance.scale).round() as u16),
+	)
+}
+
+const OVERVIEW_SCALE: f32 = 0.2;
+const OVERVIEW_MINIATURE_WIDTH: u16 = 256;
+const OVERVIEW_MINIATURE_HEIGHT: u16 = 144;
+const OVERVIEW_MINIATURE_PADDING: u16 = 4;
+/// Between rows and columns of the grid, and around it
+const OVERVIEW_SPACING: u16 = 20;
+
+pub fn overview<'a>(
+	presentation: &Presentation,
+	overview: &'a mut Overview,
+	viewport: Size,
+) -> iced::Element<'a, Message> {
+	let theme = presentation.front_matter.theme;
+	let appearance = Appearance {
+		scale: OVERVIEW_SCALE,
+		fonts: presentation.fonts,
+		theme,
+		color: theme.text_color(),
+		..Default::default()
+	};
+	let selected = overview.selected;
+	let slide_count = presentation.slides.len();
+	overview.buttons.resize_with(slide_count, Default::default);
+
+	if std::mem::take(&mut overview.scroll_to_selected) {
+		let row_height = f32::from(OVERVIEW_MINIATURE_HEIGHT + 2 * OVERVIEW_MINIATURE_PADDING);
+		let spacing = f32::from(OVERVIEW_SPACING);
+		let rows = ((slide_count + OVERVIEW_COLUMNS - 1) / OVERVIEW_COLUMNS).max(1) as f32;
+		let row_top = spacing + (selected / OVERVIEW_COLUMNS) as f32 * (row_height + spacing);
+		scroll_into_view(
+			&mut overview.scroll,
+			viewport,
+			rows * (row_height + spacing) + spacing,
+			row_top - spacing,
+			row_top + row_height + spacing,
+		);
+	}
+
+	let mut grid = Column::new()
+		.spacing(OVERVIEW_SPACING)
+		.padding(OVERVIEW_SPACING)
+		.align_items(Align::Center);
+	let mut row = Row::new().spacing(OVERVIEW_SPACING);
+
+	for (idx, (slide, button_state)) in presentation
+		.slides
+		.iter()
+		.zip(overview.buttons.iter_mut())
+		.enumerate()
+	{
+		let (align, valign) = slide_alignment(presentation, slide);
+		let appearance = Appearance {
+			align,
+			slide_area: slide_area(viewport, shows_footer(presentation, slide)),
+			..appearance
+		};
+		let miniature = Container::new(slide_nodes(
+			presentation,
+			slide,
+			appearance,
+			usize::MAX,
+			&[],
+			presentation.first_figure_number(idx),
+		))
+		.width(Length::Units(OVERVIEW_MINIATURE_WIDTH))
+		.height(Length::Units(OVERVIEW_MINIATURE_HEIGHT))
+		.center_x()
+		.align_y(vertical_align(valign));
+		let button = Button::new(button_state, miniature)
+			.padding(OVERVIEW_MINIATURE_PADDING)
+			.on_press(Message::OverviewSelected(idx))
+			.style(styles::OverviewMiniature {
+				theme,
+				selected: idx == selected,
+			});
+		row = row.push(button);
+
+		if (idx + 1) % OVERVIEW_COLUMNS == 0 || idx + 1 == slide_count {
+			let full_row = std::mem::replace(&mut row, Row::new().spacing(OVERVIEW_SPACING));
+			grid = grid.push(full_row);
+		}
+	}
+
+	Scrollable::new(&mut overview.scroll)
+		.width(Length::Fill)
+		.height(Length::Fill)
+		.push(grid)
+		.into()
+}
+
+/// Scrolls the least needed to show `top..bottom` of the content, which is `content_height`
+/// high. The scrollable has to fill the `viewport`.
+fn scroll_into_view(
+	scroll: &mut scrollable::State,
+	viewport: Size,
+	content_height: f32,
+	top: f32,
+	bottom: f32,
+) {
+	let hidden_height = content_height - viewport.height;
+	if hidden_height <= 0.0 {
+		return;
+	}
+	let bounds = Rectangle::new(Point::ORIGIN, viewport);
+	let content_bounds = Rectangle::new(Point::ORIGIN, Size::new(viewport.width, content_height));
+
+	let offset = scroll.offset(bounds, content_bounds) as f32;
+	let offset = if top < offset {
+		top
+	} else if bottom > offset + viewport.height {
+		bottom - viewport.height
+	} else {
+		return;
+	};
+	scroll.scroll_to(
+		(offset / hidden_height).clamp(0.0, 1.0),
+		bounds,
+		content_bounds,
+	);
+}
+
+static FOOTER_GRAY: [f32; 3] = [0.6, 0.6, 0.6];
+/// Text line with its padding and the progress bar
+const FOOTER_HEIGHT: u16 = 44;
+const PROGRESS_BAR_HEIGHT: u16 = 4;
+
+fn footer(presentation: &Presentation, slide_idx: usize) -> Element {
+	let slide_count = presentation.slides.len().max(1);
+	let slide_number = (slide_idx + 1).min(slide_count);
+
+	let info = Row::new()
+		.height(Length::Units(FOOTER_HEIGHT - PROGRESS_BAR_HEIGHT))
+		.padding(10)
 		.push(
-			Text::new(txt)
+			Text::new(presentation.front_matter.footer_text())
 				.width(Length::Fill)
-				// .height(Length::Fill)
-				.size(size.to_font_size())
-				.color(WHITE)
-				.font(fonts::LATO_BOLD)
-				.horizontal_alignment(HorizontalAlignment::Center)
-				.vertical_alignment(VerticalAlignment::Center),
+				.size(20)
+				.color(FOOTER_GRAY)
+				.font(presentation.fonts.body)
+				.horizontal_alignment(HorizontalAlignment::Left),
 		)
-		.into()
+		.push(
+			Text::new(format!("{} / {}", slide_number, slide_count))
+				.width(Length::Shrink)
+				.size(20)
+				.color(FOOTER_GRAY)
+				.font(presentation.fonts.body)
+				.horizontal_alignment(HorizontalAlignment::Right),
+		);
+
+	let progress_bar = ProgressBar::new(0.0..=slide_count as f32, slide_number as f32)
+		.height(Length::Units(PROGRESS_BAR_HEIGHT))
+		.style(styles::FooterProgressBar);
+
+	Column::new().push(info).push(progress_bar).into()
 }
 
-fn text(txt: &str) -> Element {
-	Text::new(txt)
-		.width(Length::Fill)
-		.size(42)
-		.color(WHITE)
-		.font(fonts::LATO_REGULAR)
-		.horizontal_alignment(HorizontalAlignment::Center)
-		.vertical_alignment(VerticalAlignment::Center)
+static WHITE: [f32; 3] = [1.0, 1.0, 1.0];
+static PLACEHOLDER_RED: [f32; 3] = [0.86, 0.2, 0.18];
+
+/// Padding of headers, wrapped text has to fit within it
+const TEXT_WRAP_MARGIN: f32 = 40.0;
+
+/// Renders `txt` with `font` at font `size`, applying `style` to it. Characters the font has no
+/// glyphs for are drawn with a fallback font, which takes a `Text` for each run of them. Rows of
+/// those don't wrap, so the runs are broken into lines here, `align`ed to each other.
+fn text_with_fallback(
+	txt: &str,
+	font: Font,
+	size: u16,
+	align: Align,
+	appearance: Appearance,
+	style: impl Fn(Text) -> Text,
+) -> Element {
+	let runs = appearance.fonts.glyphs.font_runs(txt, font);
+	if runs.len() <= 1 {
+		return style(Text::new(txt).font(font).size(size)).into();
+	}
+
+	let max_width = (appearance.slide_area.width - TEXT_WRAP_MARGIN) * appearance.scale;
+	let lines = wrap_runs(&runs, f32::from(size), max_width);
+	Column::with_children(
+		lines
+			.into_iter()
+			.map(|line| {
+				Row::with_children(
+					line.runs
+						.into_iter()
+						.map(|(font, run)| {
+							style(Text::new(run).font(font).size(size))
+								.width(Length::Shrink)
+								.into()
+						})
+						.collect(),
+				)
+				.into()
+			})
+			.collect(),
+	)
+	.align_items(align)
+	.into()
+}
+
+#[derive(Debug, Default)]
+struct WrappedLine {
+	runs: Vec<(Font, String)>,
+	width: f32,
+}
+
+impl WrappedLine {
+	fn push(&mut self, font: Font, c: char, advance: f32) {
+		match self.runs.last_mut() {
+			Some((run_font, run)) if font_name(*run_font) == font_name(font) => run.push(c),
+			_ => self.runs.push((font, c.to_string())),
+		}
+		self.width += advance;
+	}
+
+	fn append(&mut self, other: WrappedLine) {
+		for (font, run) in other.runs {
+			run.chars().for_each(|c| self.push(font, c, 0.0));
+		}
+		self.width += other.width;
+	}
+}
+
+/// Breaks `runs` into lines at most `max_width` wide at font `size`, after whitespace. Words
+/// longer than a line, like CJK text which has no spaces, are broken anywhere.
+fn wrap_runs(runs: &[(&FontFace, &str)], size: f32, max_width: f32) -> Vec<WrappedLine> {
+	let mut lines = Vec::new();
+	let mut line = WrappedLine::default();
+	let mut word = WrappedLine::default();
+	for (face, run) in runs {
+		for c in run.chars() {
+			let advance = face.advance(c, size);
+			if c.is_whitespace() {
+				line.append(std::mem::take(&mut word));
+				line.push(face.font(), c, advance);
+				continue;
+			}
+			if line.width + word.width + advance > max_width {
+				if !line.runs.is_empty() {
+					lines.push(std::mem::take(&mut line));
+				} else if !word.runs.is_empty() {
+					lines.push(std::mem::take(&mut word));
+				}
+			}
+			word.push(face.font(), c, advance);
+		}
+	}
+	line.append(word);
+	lines.push(line);
+	lines
+}
+
+fn header(size: HeaderSize, txt: &str, appearance: Appearance) -> Element {
+	Row::new()
+		.padding(20)
+		.push(text_with_fallback(
+			txt,
+			appearance.fonts.header,
+			appearance.font_size(size.to_font_size()),
+			appearance.align_x(),
+			appearance,
+			|text| {
+				text.width(Length::Fill)
+					// .height(Length::Fill)
+					.color(appearance.text_color())
+					.horizontal_alignment(appearance.horizontal_alignment())
+					.vertical_alignment(VerticalAlignment::Center)
+			},
+		))
 		.into()
 }
 
+fn text(txt: &str, appearance: Appearance) -> Element {
+	text_with_fallback(
+		txt,
+		appearance.fonts.body,
+		appearance.font_size(42),
+		appearance.align_x(),
+		appearance,
+		|text| {
+			text.width(Length::Fill)
+				.color(appearance.text_color())
+				.horizontal_alignment(appearance.horizontal_alignment())
+				.vertical_alignment(VerticalAlignment::Center)
+		},
+	)
+}
+
+/// Alt text drawn below an image, `figure` is its number if figures are numbered
+fn caption(txt: &str, figure: Option<usize>, appearance: Appearance) -> Element {
+	let txt = match figure {
+		Some(figure) => format!("Figure {}: {}", figure, txt),
+		None => txt.to_string(),
+	};
+	text_with_fallback(
+		&txt,
+		fonts::LATO_ITALIC,
+		appearance.font_size(28),
+		Align::Center,
+		appearance,
+		|text| {
+			text.color(appearance.text_color())
+				.horizontal_alignment(HorizontalAlignment::Center)
+		},
+	)
+}
+
 const BULLET_CHAR: char = '\u{2022}';
 
-fn unnumbered_list(list: &Vec<String>) -> Element {
+fn list_item(txt: &str, appearance: Appearance) -> Element {
+	text_with_fallback(
+		txt,
+		appearance.fonts.body,
+		appearance.font_size(42),
+		Align::Start,
+		appearance,
+		|text| {
+			text.width(Length::Shrink)
+				.color(appearance.text_color())
+				.horizontal_alignment(HorizontalAlignment::Left)
+				.vertical_alignment(VerticalAlignment::Center)
+		},
+	)
+}
+
+fn unnumbered_list(list: &Vec<String>, appearance: Appearance) -> Element {
 	Column::with_children(
 		list.iter()
-			.map(|element| {
-				Text::new(format!("\t{} {}", BULLET_CHAR, element))
-					.width(Length::Shrink)
-					.size(42)
-					.color(WHITE)
-					.font(fonts::LATO_REGULAR)
-					.horizontal_alignment(HorizontalAlignment::Left)
-					.vertical_alignment(VerticalAlignment::Center)
-					.into()
-			})
+			.map(|element| list_item(&format!("\t{} {}", BULLET_CHAR, element), appearance))
 			.collect(),
 	)
 	.into()
 }
 
-fn numbered_list(list: &Vec<String>) -> Element {
+fn numbered_list(list: &Vec<String>, appearance: Appearance) -> Element {
 	Column::with_children(
 		list.iter()
 			.enumerate()
-			.map(|(idx, element)| {
-				Text::new(format!("\t{}. {}", idx + 1, element))
-					.width(Length::Shrink)
-					.size(42)
-					.color(WHITE)
-					.font(fonts::LATO_REGULAR)
-					.horizontal_alignment(HorizontalAlignment::Left)
-					.vertical_alignment(VerticalAlignment::Center)
-					.into()
-			})
+			.map(|(idx, element)| list_item(&format!("\t{}. {}", idx + 1, element), appearance))
 			.collect(),
 	)
 	.into()
@@ -187,16 +999,26 @@ fn numbered_list(list: &Vec<String>) -> Element {
 // static BLUE: [f32; 3] = [3.8 / 255.0, 94.9 / 255.0, 188.6 / 255.0];
 // static RED: [f32; 3] = [193.3 / 255.0, 23.4 / 255.0, 88.5 / 255.0];
 
-fn code_block(lang: Language, params: &CodeBlockParams, txt: &str) -> Element {
+fn code_block(
+	lang: Language,
+	params: &CodeBlockParams,
+	txt: &str,
+	appearance: Appearance,
+) -> Element {
+	use std::sync::OnceLock;
 	use syntect::easy::HighlightLines;
 	use syntect::highlighting::{Color, Style, ThemeSet};
 	use syntect::parsing::SyntaxSet;
 
-	let solarized_theme = ThemeSet::load_defaults().themes["Solarized (dark)"].to_owned();
-	let syntax_set = SyntaxSet::load_defaults_newlines();
+	// Loading the defaults takes a while, and code blocks are drawn on every frame
+	static THEME_SET: OnceLock<ThemeSet> = OnceLock::new();
+	static SYNTAX_SET: OnceLock<SyntaxSet> = OnceLock::new();
+	let code_theme =
+		&THEME_SET.get_or_init(ThemeSet::load_defaults).themes[appearance.theme.code_theme()];
+	let syntax_set = SYNTAX_SET.get_or_init(SyntaxSet::load_defaults_newlines);
 
-	let font_size = params.font_size.unwrap_or(38);
-	let font = match params.font_style {
+	let font_size = appearance.font_size(params.font_size.unwrap_or(38));
+	let font = appearance.fonts.code.unwrap_or(match params.font_style {
 		Some(CodeFontStyle::Regular) => fonts::CASCADIA_CODE_REGULAR,
 		Some(CodeFontStyle::Bold) => fonts::CASCADIA_CODE_BOLD,
 		Some(CodeFontStyle::SemiBold) => fonts::CASCADIA_CODE_SEMI_BOLD,
@@ -204,7 +1026,7 @@ fn code_block(lang: Language, params: &CodeBlockParams, txt: &str) -> Element {
 		Some(CodeFontStyle::SemiLight) => fonts::CASCADIA_CODE_SEMI_LIGHT,
 		Some(CodeFontStyle::ExtraLight) => fonts::CASCADIA_CODE_EXTRA_LIGHT,
 		None => fonts::CASCADIA_CODE_REGULAR,
-	};
+	});
 
 	let syntax_ref = match lang {
 		Language::PlainText => Some(syntax_set.find_syntax_plain_text()),
@@ -212,7 +1034,8 @@ fn code_block(lang: Language, params: &CodeBlockParams, txt: &str) -> Element {
 	}
 	.unwrap_or_else(|| syntax_set.find_syntax_plain_text());
 
-	let mut highlighter = HighlightLines::new(syntax_ref, &solarized_theme);
+	let mut highlighter = HighlightLines::new(syntax_ref, code_theme);
+	let glyphs = appearance.fonts.glyphs;
 
 	let rows: Vec<Element> = txt
 		.replace("\t", "    ")
@@ -222,19 +1045,30 @@ fn code_block(lang: Language, params: &CodeBlockParams, txt: &str) -> Element {
 				line = " ";
 			}
 
-			let ranges: Vec<(Style, &str)> = highlighter.highlight(line, &syntax_set);
+			let ranges: Vec<(Style, &str)> = highlighter.highlight(line, syntax_set);
 
 			Row::with_children(
 				ranges
 					.into_iter()
-					.map(|(style, str)| {
+					.flat_map(|(style, str)| {
 						let Color { r, b, g, a } = style.foreground;
-						Text::new(str)
-							.width(Length::Shrink)
-							.size(font_size)
-							.color(iced::Color::from_rgba8(r, g, b, f32::from(a) / 255.0))
-							.font(font)
-							.into()
+						let color = iced::Color::from_rgba8(
+							r,
+							g,
+							b,
+							f32::from(a) / 255.0 * appearance.opacity,
+						);
+						glyphs
+							.font_runs(str, font)
+							.into_iter()
+							.map(move |(face, run)| {
+								Text::new(run)
+									.width(Length::Shrink)
+									.size(font_size)
+									.color(color)
+									.font(face.font())
+									.into()
+							})
 					})
 					.collect(),
 			)
@@ -244,30 +1078,189 @@ fn code_block(lang: Language, params: &CodeBlockParams, txt: &str) -> Element {
 
 	Container::new(Column::with_children(rows))
 		.padding(10)
-		.style(styles::CodeBlockContainer)
+		.style(styles::CodeBlockContainer(appearance.theme))
 		.into()
 }
 
 static SOLARIZED_BASE03: [f32; 3] = [0.0 / 255.0, 43.0 / 255.0, 54.0 / 255.0];
+static SOLARIZED_BASE3: [f32; 3] = [253.0 / 255.0, 246.0 / 255.0, 227.0 / 255.0];
+static NEAR_BLACK: [f32; 3] = [0.13, 0.13, 0.13];
+
+impl Theme {
+	pub fn background(self) -> Color {
+		match self {
+			Theme::Dark => Color::BLACK,
+			Theme::Light => Color::WHITE,
+		}
+	}
+
+	fn text_color(self) -> Color {
+		match self {
+			Theme::Dark => WHITE.into(),
+			Theme::Light => NEAR_BLACK.into(),
+		}
+	}
+
+	/// Name of the syntect theme code blocks are highlighted with
+	fn code_theme(self) -> &'static str {
+		match self {
+			Theme::Dark => "Solarized (dark)",
+			Theme::Light => "Solarized (light)",
+		}
+	}
+
+	fn code_background(self) -> Color {
+		match self {
+			Theme::Dark => SOLARIZED_BASE03.into(),
+			Theme::Light => SOLARIZED_BASE3.into(),
+		}
+	}
+}
+
+/// Centers the slide, drawn in the `area` of the window with the theme's background
+pub fn letterbox<'a>(
+	slide: iced::Element<'a, Message>,
+	area: Size,
+	theme: Theme,
+) -> iced::Element<'a, Message> {
+	let slide = Container::new(slide)
+		.width(Length::Units(area.width as u16))
+		.height(Length::Units(area.height as u16))
+		.style(styles::SlideArea(theme));
+	Container::new(slide)
+		.width(Length::Fill)
+		.height(Length::Fill)
+		.center_x()
+		.center_y()
+		.into()
+}
 
 mod styles {
-	use crate::views::SOLARIZED_BASE03;
+	use crate::views::{FOOTER_GRAY, PLACEHOLDER_RED, WHITE};
+	use crate::Theme;
 	use iced::container::{self, Style};
-	use iced::Background;
+	use iced::{button, progress_bar, Background, Color};
 
-	pub struct CodeBlockContainer;
+	pub struct CodeBlockContainer(pub Theme);
 
 	impl container::StyleSheet for CodeBlockContainer {
 		fn style(&self) -> Style {
 			container::Style {
 				text_color: None,
-				background: Some(Background::Color(SOLARIZED_BASE03.into())),
+				background: Some(Background::Color(self.0.code_background())),
 				border_radius: 10.0,
 				border_width: 0.0,
 				border_color: Default::default(),
 			}
 		}
 	}
+
+	pub struct SlideArea(pub Theme);
+
+	impl container::StyleSheet for SlideArea {
+		fn style(&self) -> Style {
+			container::Style {
+				background: Some(Background::Color(self.0.background())),
+				..container::Style::default()
+			}
+		}
+	}
+
+	pub struct Blank(pub crate::Blank);
+
+	impl container::StyleSheet for Blank {
+		fn style(&self) -> Style {
+			let color = match self.0 {
+				crate::Blank::Black => Color::BLACK,
+				crate::Blank::White => Color::WHITE,
+			};
+			container::Style {
+				background: Some(Background::Color(color)),
+				..container::Style::default()
+			}
+		}
+	}
+
+	pub struct ImagePlaceholder;
+
+	impl container::StyleSheet for ImagePlaceholder {
+		fn style(&self) -> Style {
+			container::Style {
+				text_color: None,
+				background: None,
+				border_radius: 4.0,
+				border_width: 2.0,
+				border_color: PLACEHOLDER_RED.into(),
+			}
+		}
+	}
+
+	pub struct OverviewMiniature {
+		pub theme: Theme,
+		pub selected: bool,
+	}
+
+	impl button::StyleSheet for OverviewMiniature {
+		fn active(&self) -> button::Style {
+			button::Style {
+				background: Some(Background::Color(self.theme.background())),
+				border_radius: 4.0,
+				border_width: if self.selected { 3.0 } else { 1.0 },
+				border_color: if self.selected {
+					self.theme.text_color()
+				} else {
+					FOOTER_GRAY.into()
+				},
+				..button::Style::default()
+			}
+		}
+
+		fn hovered(&self) -> button::Style {
+			button::Style {
+				border_color: self.theme.text_color(),
+				..self.active()
+			}
+		}
+	}
+
+	pub struct WelcomeEntry {
+		pub selected: bool,
+	}
+
+	impl button::StyleSheet for WelcomeEntry {
+		fn active(&self) -> button::Style {
+			button::Style {
+				background: None,
+				border_radius: 4.0,
+				border_width: 1.0,
+				border_color: if self.selected {
+					WHITE.into()
+				} else {
+					Color::TRANSPARENT
+				},
+				..button::Style::default()
+			}
+		}
+
+		fn hovered(&self) -> button::Style {
+			button::Style {
+				border_color: if self.selected { WHITE } else { FOOTER_GRAY }.into(),
+				..self.active()
+			}
+		}
+	}
+
+	pub struct FooterProgressBar;
+
+	impl progress_bar::StyleSheet for FooterProgressBar {
+		fn style(&self) -> progress_bar::Style {
+			progress_bar::Style {
+				background: Background::Color(Color::TRANSPARENT),
+				bar: Background::Color(FOOTER_GRAY.into()),
+				border_radius: 0.0,
+			}
+		}
+	}
 }
 
 #[allow(dead_code)]
@@ -301,5 +1294,27 @@ pub mod fonts {
 		CASCADIA_CODE_REGULAR: "CascadiaCode-Regular.ttf",
 		CASCADIA_CODE_SEMI_BOLD: "CascadiaCode-SemiBold.ttf",
 		CASCADIA_CODE_SEMI_LIGHT: "CascadiaCode-SemiLight.ttf",
+
+		DEJAVU_SANS: "DejaVuSans.ttf",
+	];
+
+	/// Fonts slides are drawn with, apart from custom ones. DejaVu Sans is only a fallback.
+	pub static EMBEDDED: &[iced::Font] = &[
+		LATO_BLACK,
+		LATO_BLACK_ITALIC,
+		LATO_BOLD,
+		LATO_BOLD_ITALIC,
+		LATO_ITALIC,
+		LATO_LIGHT,
+		LATO_LIGHT_ITALIC,
+		LATO_REGULAR,
+		LATO_THIN,
+		LATO_THINITALIC,
+		CASCADIA_CODE_BOLD,
+		CASCADIA_CODE_EXTRA_LIGHT,
+		CASCADIA_CODE_LIGHT,
+		CASCADIA_CODE_REGULAR,
+		CASCADIA_CODE_SEMI_BOLD,
+		CASCADIA_CODE_SEMI_LIGHT,
 	];
 }